@@ -3,13 +3,16 @@ use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
     Addr, to_binary, DepsMut, Env, MessageInfo, Response,
-    Uint128, CosmosMsg, WasmMsg, Storage
+    Uint128, CosmosMsg, WasmMsg, Storage, Event
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw20::{Cw20ExecuteMsg, Cw20QueryMsg, BalanceResponse as Cw20BalanceResponse, TokenInfoResponse};
 
 use crate::error::ContractError;
-use Interface::staking::{ExecuteMsg, InstantiateMsg, UserInfo, CardInfo, CardType};
+use Interface::staking::{ExecuteMsg, InstantiateMsg, MigrateMsg, UserInfo, CardInfo, CardType};
+use Interface::version::parse_version;
+use Interface::events::{EVENT_STAKE, EVENT_UNSTAKE, EVENT_REWARD_CLAIM, EVENT_TIER_CHANGE,
+    ATTR_WALLET, ATTR_AMOUNT, ATTR_OLD_TIER, ATTR_NEW_TIER, ATTR_CARD_NUMBER};
 use crate::state::{USER_INFOS, CARD_INFOS, OWNER, REWARD_TOKEN, START_TIME, 
     PLATIUM_CARD_NUMBER, GOLD_CARD_NUMBER, SILVER_CARD_NUMBER, BRONZE_CARD_NUMBER, DECIMALS};
 
@@ -65,6 +68,26 @@ pub fn instantiate(
         .add_attribute("method", "instantiate"))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::InvalidContractName{ name: stored.contract });
+    }
+    let version = parse_version(&stored.version)?;
+    if version > parse_version(CONTRACT_VERSION)? {
+        return Err(ContractError::CannotDowngrade{ version: stored.version });
+    }
+
+    //-----------no state transforms yet, layout unchanged since 0.0.1-----------
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -159,6 +182,7 @@ pub fn try_deposit(
     };
 
     let card_type = get_cardtype(deps.storage, user_info.amount)?;
+    let old_type = user_info.card_type.clone();
     user_info.card_number = manage_card(
         deps.storage, 
         wallet.clone(),
@@ -168,8 +192,14 @@ pub fn try_deposit(
     )?;
     user_info.card_type = card_type.clone();
 
+    let mut events = vec![Event::new(EVENT_STAKE)
+        .add_attribute(ATTR_WALLET, wallet.to_string())
+        .add_attribute(ATTR_AMOUNT, amount.to_string())];
+    events.extend(tier_change_event(&old_type, &user_info));
+
     USER_INFOS.save(deps.storage, wallet, &user_info)?;
     Ok(Response::new()
+        .add_events(events)
         .add_attribute("action", "desposit"))
 }
 
@@ -196,6 +226,7 @@ pub fn try_withdraw(
 
     user_info.amount -= amount;
     let card_type = get_cardtype(deps.storage, user_info.amount)?;
+    let old_type = user_info.card_type.clone();
     user_info.card_number = manage_card(
         deps.storage, 
         wallet.clone(),
@@ -217,7 +248,13 @@ pub fn try_withdraw(
         )?, 
         funds: vec![]
     };
+    let mut events = vec![Event::new(EVENT_UNSTAKE)
+        .add_attribute(ATTR_WALLET, wallet.to_string())
+        .add_attribute(ATTR_AMOUNT, amount.to_string())];
+    events.extend(tier_change_event(&old_type, &user_info));
+
     Ok(Response::new()
+        .add_events(events)
         .add_attribute("action", "withdraw")
         .add_message(msg)
     )
@@ -251,11 +288,26 @@ pub fn try_claimrewards(
         )?, 
         funds: vec![]
     };
+    let event = Event::new(EVENT_REWARD_CLAIM)
+        .add_attribute(ATTR_WALLET, wallet.to_string())
+        .add_attribute(ATTR_AMOUNT, user_info.reward_amount.to_string());
     user_info.reward_amount = Uint128::zero();
 
     USER_INFOS.save(deps.storage, wallet, &user_info)?;
     Ok(Response::new()
+        .add_event(event)
         .add_attribute("action", "claim rewards")
         .add_message(msg)
     )
-}
\ No newline at end of file
+}
+
+fn tier_change_event(old_type: &CardType, user_info: &UserInfo) -> Option<Event> {
+    if *old_type == user_info.card_type {
+        return None;
+    }
+    Some(Event::new(EVENT_TIER_CHANGE)
+        .add_attribute(ATTR_WALLET, user_info.wallet.to_string())
+        .add_attribute(ATTR_OLD_TIER, format!("{:?}", old_type))
+        .add_attribute(ATTR_NEW_TIER, format!("{:?}", user_info.card_type))
+        .add_attribute(ATTR_CARD_NUMBER, user_info.card_number.to_string()))
+}
@@ -18,6 +18,12 @@ pub enum ContractError {
 
     #[error("Staking error: {:?}", msg)]
     StakingError {msg: String},
+
+    #[error("Cannot migrate from contract {name}")]
+    InvalidContractName {name: String},
+
+    #[error("Cannot downgrade from version {version}")]
+    CannotDowngrade {version: String},
     
     #[error("Not correct Milestone status : {aust_balance} {estimate_exchange_rate} {epoch_exchange_rate} {withdraw_amount} {release_amount}")]
     Testing{
@@ -6,7 +6,7 @@ use cosmwasm_std::{
    from_binary, from_slice, to_binary, Coin, ContractResult, CustomQuery, Empty,
    OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
-use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg};
+use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 
 use std::collections::HashMap;
 use std::marker::PhantomData;
@@ -111,6 +111,15 @@ impl<C: CustomQuery + DeserializeOwned> WasmMockQuerier<C> {
                      to_binary(&Cw20BalanceResponse { balance }).unwrap(),
                   ))
                }
+               Cw20QueryMsg::TokenInfo {} => SystemResult::Ok(ContractResult::Ok(
+                  to_binary(&TokenInfoResponse {
+                     name: contract_addr.to_string(),
+                     symbol: String::from("WFD"),
+                     decimals: 6,
+                     total_supply: Uint128::zero(),
+                  })
+                  .unwrap(),
+               )),
                _ => panic!("DO NOT ENTER HERE"),
             }
          }
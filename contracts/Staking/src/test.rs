@@ -1,11 +1,13 @@
 use super::*;
-use cosmwasm_std::{from_binary, Addr, CosmosMsg, WasmMsg,
+use cosmwasm_std::{from_binary, Addr, CosmosMsg, WasmMsg, Event,
     BankQuery, BalanceResponse, AllBalanceResponse, Coin, Uint128, Api};
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 
 use crate::contract::{execute, instantiate};
 use crate::query::{query};
 use Interface::staking::{ExecuteMsg, InstantiateMsg, QueryMsg, UserInfo, CardInfo, CardType};
+use Interface::events::{EVENT_STAKE, EVENT_UNSTAKE, EVENT_TIER_CHANGE, ATTR_WALLET, ATTR_AMOUNT,
+    ATTR_OLD_TIER, ATTR_NEW_TIER, ATTR_CARD_NUMBER};
 
 use crate::mock_querier::{mock_dependencies};
 use cw20::Cw20ExecuteMsg;
@@ -111,3 +113,57 @@ fn workflow(){
     println!("Card info {:?}", res );    
 }
 
+
+#[test]
+fn staking_events() {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(
+        &"wfd".to_string(),
+        &[(&MOCK_CONTRACT_ADDR.to_string(), &Uint128::from(1_000_000_000u128))],
+    )]);
+    let msg = InstantiateMsg {
+        owner: Some(String::from("owner")),
+        start_time: Some(Uint128::from(1u128)),
+        reward_token: Some(String::from("wfd")),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+
+    let user1 = Addr::unchecked("user1");
+    let msg = ExecuteMsg::Deposit {
+        wallet: user1.clone(),
+        amount: Uint128::from(1_000_000_000u128),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), msg).unwrap();
+    assert_eq!(
+        res.events,
+        vec![
+            Event::new(EVENT_STAKE)
+                .add_attribute(ATTR_WALLET, "user1")
+                .add_attribute(ATTR_AMOUNT, "1000000000"),
+            Event::new(EVENT_TIER_CHANGE)
+                .add_attribute(ATTR_WALLET, "user1")
+                .add_attribute(ATTR_OLD_TIER, "Other")
+                .add_attribute(ATTR_NEW_TIER, "Bronze")
+                .add_attribute(ATTR_CARD_NUMBER, "1"),
+        ]
+    );
+
+    let msg = ExecuteMsg::Withdraw {
+        wallet: user1,
+        amount: Uint128::from(500_000_000u128),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("user1", &[]), msg).unwrap();
+    assert_eq!(
+        res.events,
+        vec![
+            Event::new(EVENT_UNSTAKE)
+                .add_attribute(ATTR_WALLET, "user1")
+                .add_attribute(ATTR_AMOUNT, "500000000"),
+            Event::new(EVENT_TIER_CHANGE)
+                .add_attribute(ATTR_WALLET, "user1")
+                .add_attribute(ATTR_OLD_TIER, "Bronze")
+                .add_attribute(ATTR_NEW_TIER, "Other")
+                .add_attribute(ATTR_CARD_NUMBER, "0"),
+        ]
+    );
+}
@@ -2,18 +2,24 @@
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    to_binary, Addr, CosmosMsg, DepsMut, Env, MessageInfo, Response, Storage, Uint128, Uint64,
-    WasmMsg,
+    to_binary, Addr, CosmosMsg, DepsMut, Env, Event, MessageInfo, Order, Response, StdResult,
+    Storage, Uint128, Uint64, WasmMsg,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw20::{
     BalanceResponse as Cw20BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, TokenInfoResponse,
 };
 
 use crate::error::ContractError;
-use crate::state::{OWNER, PROJECT_INFOS};
+use crate::migrate::migrate_projects_v0_1;
+use crate::state::{OWNER, PROJECT_INFOS, USERS};
+use Interface::events::{
+    ATTR_AMOUNT, ATTR_PROJECT_ID, ATTR_RECIPIENT, ATTR_STAGE, ATTR_WALLET, EVENT_VESTING_CLAIM,
+    EVENT_VESTING_USER_ADDED, EVENT_VESTING_USER_TRANSFERRED,
+};
+use Interface::version::parse_version;
 use Interface::vesting::{
-    Config, ExecuteMsg, InstantiateMsg, ProjectInfo, UserInfo, VestingParameter,
+    Config, ExecuteMsg, InstantiateMsg, MigrateMsg, ProjectInfo, UserInfo, VestingParameter,
 };
 
 // version info for migration info
@@ -38,6 +44,33 @@ pub fn instantiate(
     Ok(Response::new().add_attribute("method", "instantiate"))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::InvalidContractName {
+            name: stored.contract,
+        });
+    }
+    let version = parse_version(&stored.version)?;
+    if version > parse_version(CONTRACT_VERSION)? {
+        return Err(ContractError::CannotDowngrade {
+            version: stored.version,
+        });
+    }
+
+    //-----------state transforms, in version order---------------------
+    if version < parse_version("0.1.0")? {
+        migrate_projects_v0_1(deps.branch())?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -98,6 +131,14 @@ pub fn execute(
             amount,
         } => try_adduser(deps, info, project_id, stage, wallet, amount),
 
+        ExecuteMsg::TransferUser {
+            project_id,
+            stage,
+            from,
+            to,
+            amount,
+        } => try_transferuser(deps, info, project_id, stage, from, to, amount),
+
         ExecuteMsg::ClaimPendingTokens { project_id } => {
             try_claimpendingtokens(deps, _env, info, project_id)
         }
@@ -192,21 +233,15 @@ pub fn try_claimpendingtokens(
     info: MessageInfo,
     project_id: Uint64,
 ) -> Result<Response, ContractError> {
-    let mut x = PROJECT_INFOS.load(deps.storage, project_id.u64())?;
+    let x = PROJECT_INFOS.load(deps.storage, project_id.u64())?;
     let mut amount = Uint128::zero();
-    for i in 0..x.users.len() - 1 {
-        let index = x.users[i]
-            .iter()
-            .position(|x| x.wallet_address == info.sender);
-        if index != None {
-            let pending_amount = calc_pending(
-                deps.storage,
-                _env.clone(),
-                project_id,
-                x.users[i][index.unwrap()].clone(),
-                i,
-            );
-            x.users[i][index.unwrap()].released_amount += pending_amount;
+    for i in 0..x.total.len() - 1 {
+        let key = (project_id.u64(), i as u64, &info.sender);
+        if let Some(mut user) = USERS.may_load(deps.storage, key)? {
+            let pending_amount =
+                calc_pending(deps.storage, _env.clone(), project_id, user.clone(), i);
+            user.released_amount += pending_amount;
+            USERS.save(deps.storage, key, &user)?;
             amount += pending_amount;
         }
     }
@@ -215,8 +250,6 @@ pub fn try_claimpendingtokens(
         return Err(ContractError::NoPendingTokens {});
     }
 
-    PROJECT_INFOS.save(deps.storage, project_id.u64(), &x)?;
-
     let token_info: TokenInfoResponse = deps
         .querier
         .query_wasm_smart(x.config.token_addr.clone(), &Cw20QueryMsg::TokenInfo {})?;
@@ -244,21 +277,36 @@ pub fn try_claimpendingtokens(
 
     Ok(Response::new()
         .add_message(CosmosMsg::Wasm(bank_cw20))
+        .add_event(
+            Event::new(EVENT_VESTING_CLAIM)
+                .add_attribute(ATTR_PROJECT_ID, project_id.to_string())
+                .add_attribute(ATTR_WALLET, info.sender.to_string())
+                .add_attribute(ATTR_AMOUNT, amount.to_string()),
+        )
         .add_attribute("action", "Claim pending tokens"))
 }
 
-pub fn check_add_userinfo(users: &mut Vec<UserInfo>, wallet: Addr, amount: Uint128) {
-    let index = users.iter().position(|x| x.wallet_address == wallet);
-    if index == None {
-        users.push(UserInfo {
-            wallet_address: wallet,
+pub fn check_add_userinfo(
+    store: &mut dyn Storage,
+    project_id: u64,
+    stage: u64,
+    wallet: Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let key = (project_id, stage, &wallet);
+    let user = match USERS.may_load(store, key)? {
+        None => UserInfo {
+            wallet_address: wallet.clone(),
             total_amount: amount,
             released_amount: Uint128::zero(),
             pending_amount: Uint128::zero(),
-        });
-    } else {
-        users[index.unwrap()].total_amount += amount;
-    }
+        },
+        Some(mut user) => {
+            user.total_amount += amount;
+            user
+        }
+    };
+    USERS.save(store, key, &user)
 }
 pub fn try_adduser(
     deps: DepsMut,
@@ -274,11 +322,90 @@ pub fn try_adduser(
         return Err(ContractError::Unauthorized {});
     }
 
-    check_add_userinfo(&mut x.users[stage.u128() as usize], wallet, amount);
     x.total[stage.u128() as usize] += amount;
+    check_add_userinfo(
+        deps.storage,
+        project_id.u64(),
+        stage.u128() as u64,
+        wallet.clone(),
+        amount,
+    )?;
     PROJECT_INFOS.save(deps.storage, project_id.u64(), &x)?;
 
-    Ok(Response::new().add_attribute("action", "Add  User info"))
+    Ok(Response::new()
+        .add_event(
+            Event::new(EVENT_VESTING_USER_ADDED)
+                .add_attribute(ATTR_PROJECT_ID, project_id.to_string())
+                .add_attribute(ATTR_STAGE, stage.to_string())
+                .add_attribute(ATTR_WALLET, wallet.to_string())
+                .add_attribute(ATTR_AMOUNT, amount.to_string()),
+        )
+        .add_attribute("action", "Add  User info"))
+}
+
+//-----------moves part of allocation with its released share to another wallet-------
+pub fn try_transferuser(
+    deps: DepsMut,
+    info: MessageInfo,
+    project_id: Uint64,
+    stage: Uint128,
+    from: Addr,
+    to: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let x = PROJECT_INFOS.load(deps.storage, project_id.u64())?;
+    let owner = OWNER.load(deps.storage).unwrap();
+    if info.sender != owner && info.sender != x.config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let stage = stage.u128() as u64;
+    let key = (project_id.u64(), stage, &from);
+    let mut user = USERS.may_load(deps.storage, key)?.unwrap_or(UserInfo {
+        wallet_address: from.clone(),
+        total_amount: Uint128::zero(),
+        released_amount: Uint128::zero(),
+        pending_amount: Uint128::zero(),
+    });
+    if user.total_amount < amount {
+        return Err(ContractError::NotEnoughAllocation {});
+    }
+    if amount.is_zero() || from == to {
+        return Ok(Response::new().add_attribute("action", "Transfer user info"));
+    }
+
+    let released = user
+        .released_amount
+        .multiply_ratio(amount, user.total_amount);
+    user.total_amount -= amount;
+    user.released_amount -= released;
+    if user.total_amount.is_zero() {
+        USERS.remove(deps.storage, key);
+    } else {
+        USERS.save(deps.storage, key, &user)?;
+    }
+
+    check_add_userinfo(deps.storage, project_id.u64(), stage, to.clone(), amount)?;
+    USERS.update(
+        deps.storage,
+        (project_id.u64(), stage, &to),
+        |user| -> StdResult<_> {
+            let mut user = user.unwrap();
+            user.released_amount += released;
+            Ok(user)
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new(EVENT_VESTING_USER_TRANSFERRED)
+                .add_attribute(ATTR_PROJECT_ID, project_id.to_string())
+                .add_attribute(ATTR_STAGE, stage.to_string())
+                .add_attribute(ATTR_WALLET, from.to_string())
+                .add_attribute(ATTR_RECIPIENT, to.to_string())
+                .add_attribute(ATTR_AMOUNT, amount.to_string()),
+        )
+        .add_attribute("action", "Transfer user info"))
 }
 
 pub fn try_setusers(
@@ -294,9 +421,22 @@ pub fn try_setusers(
         return Err(ContractError::Unauthorized {});
     }
 
-    x.users[stage.u128() as usize] = user_infos;
-
-    PROJECT_INFOS.save(deps.storage, project_id.u64(), &x)?;
+    //-----------replace all users of the stage---------------------
+    let prefix = (project_id.u64(), stage.u128() as u64);
+    let wallets: StdResult<Vec<Addr>> = USERS
+        .prefix(prefix)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect();
+    for wallet in wallets? {
+        USERS.remove(deps.storage, (prefix.0, prefix.1, &wallet));
+    }
+    for user in user_infos {
+        USERS.save(
+            deps.storage,
+            (prefix.0, prefix.1, &user.wallet_address),
+            &user,
+        )?;
+    }
 
     Ok(Response::new().add_attribute("action", "Set User infos for Seed stage"))
 }
@@ -367,16 +507,13 @@ pub fn try_addproject(
     }
 
     let _project_info = PROJECT_INFOS.may_load(deps.storage, project_id.u64())?;
-    let mut users = Vec::new();
     let mut total = Vec::new();
 
     if _project_info != None {
         let _project_info = _project_info.unwrap();
-        users = _project_info.users;
         total = _project_info.total;
     } else {
         for _ in _vesting_params.clone() {
-            users.push(Vec::new());
             total.push(Uint128::zero())
         }
     }
@@ -385,7 +522,6 @@ pub fn try_addproject(
         project_id: project_id,
         config: config,
         vest_param: _vesting_params,
-        users: users,
         total: total,
     };
 
@@ -16,6 +16,15 @@ pub enum ContractError {
     #[error("There is no enough tokens")]
     NotEnoughBalance {},
 
+    #[error("Not enough allocation to transfer")]
+    NotEnoughAllocation {},
+
+    #[error("Cannot migrate from contract {name}")]
+    InvalidContractName { name: String },
+
+    #[error("Cannot downgrade from version {version}")]
+    CannotDowngrade { version: String },
+
     #[error("Not correct Milestone status : {aust_balance} {estimate_exchange_rate} {epoch_exchange_rate} {withdraw_amount} {release_amount}")]
     Testing{
         aust_balance: String,
@@ -1,4 +1,5 @@
 pub mod contract;
+pub mod migrate;
 pub mod query;
 mod error;
 pub mod state;
@@ -0,0 +1,54 @@
+use cosmwasm_std::{DepsMut, Order, StdResult, Uint128, Uint64};
+use cw_storage_plus::Map;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+use crate::state::{PROJECT_INFOS, USERS};
+use Interface::vesting::{Config, ProjectInfo, UserInfo, VestingParameter};
+
+//------------layout stored before 0.1.0-----------------------------------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyProjectInfo {
+    pub project_id: Uint64,
+    pub config: Config,
+    pub vest_param: Vec<VestingParameter>,
+    pub users: Vec<Vec<UserInfo>>,
+    pub total: Vec<Uint128>,
+}
+
+pub const LEGACY_PROJECT_INFOS: Map<u64, LegacyProjectInfo> = Map::new("project_infos");
+
+//------------move users of every stage out of project info--------------------
+pub fn migrate_projects_v0_1(deps: DepsMut) -> Result<(), ContractError> {
+    let legacy: StdResult<Vec<_>> = LEGACY_PROJECT_INFOS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect();
+
+    for (id, old) in legacy? {
+        for (stage, users) in old.users.into_iter().enumerate() {
+            for user in users {
+                //-----same wallet listed twice in a stage is merged-----
+                let key = (id, stage as u64, &user.wallet_address);
+                let user = match USERS.may_load(deps.storage, key)? {
+                    None => user.clone(),
+                    Some(mut merged) => {
+                        merged.total_amount += user.total_amount;
+                        merged.released_amount += user.released_amount;
+                        merged
+                    }
+                };
+                USERS.save(deps.storage, key, &user)?;
+            }
+        }
+
+        let project_info = ProjectInfo {
+            project_id: old.project_id,
+            config: old.config,
+            vest_param: old.vest_param,
+            total: old.total,
+        };
+        PROJECT_INFOS.save(deps.storage, id, &project_info)?;
+    }
+    Ok(())
+}
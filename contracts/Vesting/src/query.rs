@@ -4,11 +4,12 @@ use cosmwasm_std::{
     to_binary, AllBalanceResponse, BankQuery, Binary, Coin, Deps, Env, QueryRequest, StdResult,
     Uint128, Uint64
 };
+use cw_storage_plus::Bound;
 
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
 
 use crate::contract::calc_pending;
-use crate::state::{OWNER, PROJECT_INFOS};
+use crate::state::{OWNER, PROJECT_INFOS, USERS};
 use Interface::vesting::{Config, ProjectInfo, QueryMsg, UserInfo};
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -28,6 +29,13 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_binary(&query_pendingtokens(deps, _env, project_id, wallet)?)
         }
 
+        QueryMsg::GetUsers {
+            project_id,
+            stage,
+            start_after,
+            limit,
+        } => to_binary(&query_users(deps, project_id, stage, start_after, limit)?),
+
         QueryMsg::GetAllProjectInfo {} => to_binary(&query_getallprojectinfo(deps)?),
 
         QueryMsg::GetOwner {} => {
@@ -47,18 +55,13 @@ fn query_pendingtokens(
     wallet: String,
 ) -> StdResult<Uint128> {
     let x = PROJECT_INFOS.load(deps.storage, project_id.u64())?;
+    let wallet = deps.api.addr_validate(&wallet)?;
 
     let mut amount = Uint128::zero();
-    for i in 0..x.users.len() - 1 {
-        let index = x.users[i].iter().position(|x| x.wallet_address == wallet);
-        if index != None {
-            let pending_amount = calc_pending(
-                deps.storage,
-                _env.clone(),
-                project_id,
-                x.users[i][index.unwrap()].clone(),
-                i,
-            );
+    for i in 0..x.total.len() - 1 {
+        let user = USERS.may_load(deps.storage, (project_id.u64(), i as u64, &wallet))?;
+        if let Some(user) = user {
+            let pending_amount = calc_pending(deps.storage, _env.clone(), project_id, user, i);
             amount += pending_amount;
         }
     }
@@ -124,13 +127,37 @@ fn query_getuserinfo(deps: Deps, project_id: Uint64, wallet: String) -> StdResul
         pending_amount: Uint128::zero(),
     };
 
-    for i in 0..x.users.len() - 1 {
-        let index = x.users[i].iter().position(|x| x.wallet_address == wallet);
-        if index != None {
-            user_info.total_amount += x.users[i][index.unwrap()].total_amount;
-            user_info.released_amount += x.users[i][index.unwrap()].released_amount;
+    for i in 0..x.total.len() - 1 {
+        let key = (project_id.u64(), i as u64, &user_info.wallet_address);
+        if let Some(user) = USERS.may_load(deps.storage, key)? {
+            user_info.total_amount += user.total_amount;
+            user_info.released_amount += user.released_amount;
         }
     }
 
     Ok(user_info)
 }
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+fn query_users(
+    deps: Deps,
+    project_id: Uint64,
+    stage: Uint128,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<UserInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|x| deps.api.addr_validate(&x))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    USERS
+        .prefix((project_id.u64(), stage.u128() as u64))
+        .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, user)| user))
+        .collect()
+}
@@ -2,8 +2,11 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use cosmwasm_std::{Addr, Uint64};
 use cw_storage_plus::{Item, Map};
-use Interface::vesting::{ProjectInfo};
+use Interface::vesting::{ProjectInfo, UserInfo};
 
 pub const OWNER: Item<Addr> = Item::new("owner");
 
 pub const PROJECT_INFOS:Map<u64, ProjectInfo> = Map::new("project_infos");
+
+//------------users by (project_id, stage, wallet)-----------------------------
+pub const USERS: Map<(u64, u64, &Addr), UserInfo> = Map::new("users");
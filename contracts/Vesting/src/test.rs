@@ -1,14 +1,21 @@
 use super::*;
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Coin, CosmosMsg, Uint128,
-    WasmMsg, Uint64,
+    from_binary, Addr, AllBalanceResponse, BalanceResponse, BankQuery, Coin, CosmosMsg, Event,
+    Uint128, WasmMsg, Uint64,
 };
 
-use crate::contract::{execute, instantiate};
+use crate::contract::{execute, instantiate, migrate};
+use crate::migrate::{LegacyProjectInfo, LEGACY_PROJECT_INFOS};
 use crate::query::query;
+use cw2::set_contract_version;
+use Interface::events::{
+    ATTR_AMOUNT, ATTR_PROJECT_ID, ATTR_RECIPIENT, ATTR_STAGE, ATTR_WALLET, EVENT_VESTING_CLAIM,
+    EVENT_VESTING_USER_TRANSFERRED,
+};
 use Interface::vesting::{
-    Config, ExecuteMsg, InstantiateMsg, ProjectInfo, QueryMsg, UserInfo, VestingParameter,
+    Config, ExecuteMsg, InstantiateMsg, MigrateMsg, ProjectInfo, QueryMsg, UserInfo,
+    VestingParameter,
 };
 
 use crate::mock_querier::mock_dependencies;
@@ -59,3 +66,130 @@ fn workflow() {
     let res: ProjectInfo = from_binary(&project_info).unwrap();
     println!("Project Info {:?}", res);
 }
+
+#[test]
+fn migrate_legacy_users() {
+    let mut deps = mock_dependencies(&[]);
+    set_contract_version(deps.as_mut().storage, "Vesting", "0.0.1").unwrap();
+
+    let user = |wallet: &str, total: u128, released: u128| UserInfo {
+        wallet_address: Addr::unchecked(wallet),
+        total_amount: Uint128::new(total),
+        released_amount: Uint128::new(released),
+        pending_amount: Uint128::zero(),
+    };
+    let param = VestingParameter {
+        soon: Uint128::new(15),
+        after: Uint128::new(100),
+        period: Uint128::new(600),
+    };
+    let legacy = LegacyProjectInfo {
+        project_id: Uint64::new(1),
+        config: Config {
+            owner: Addr::unchecked("admin"),
+            token_addr: String::from("WeFund"),
+            start_time: Uint128::zero(),
+        },
+        vest_param: vec![param, param, param],
+        users: vec![
+            vec![user("seed1", 100, 10), user("seed2", 50, 0)],
+            vec![user("seed1", 30, 0)],
+            Vec::new(),
+        ],
+        total: vec![Uint128::new(150), Uint128::new(30), Uint128::zero()],
+    };
+    LEGACY_PROJECT_INFOS
+        .save(deps.as_mut().storage, 1, &legacy)
+        .unwrap();
+
+    set_contract_version(deps.as_mut().storage, "Vesting", "1.0.0").unwrap();
+    assert!(migrate(deps.as_mut(), mock_env(), MigrateMsg {}).is_err());
+    set_contract_version(deps.as_mut().storage, "Vesting", "0.0.1").unwrap();
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    let msg = QueryMsg::GetUsers {
+        project_id: Uint64::new(1),
+        stage: Uint128::zero(),
+        start_after: None,
+        limit: None,
+    };
+    let res: Vec<UserInfo> =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res, vec![user("seed1", 100, 10), user("seed2", 50, 0)]);
+
+    let msg = QueryMsg::GetUserInfo {
+        project_id: Uint64::new(1),
+        wallet: String::from("seed1"),
+    };
+    let res: UserInfo =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.total_amount, Uint128::new(130));
+    assert_eq!(res.released_amount, Uint128::new(10));
+}
+
+#[test]
+fn vesting_events() {
+    let mut deps = mock_dependencies(&[]);
+    let info = mock_info("admin", &[]);
+    let msg = InstantiateMsg {
+        admin: Some(String::from("admin")),
+    };
+    instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let param = VestingParameter {
+        soon: Uint128::new(15),
+        after: Uint128::new(100),
+        period: Uint128::new(600),
+    };
+    let start_time = mock_env().block.time.seconds() - 1000;
+    let msg = ExecuteMsg::AddProject {
+        project_id: Uint64::new(1),
+        admin: String::from("admin"),
+        token_addr: String::from("WeFund"),
+        vesting_params: vec![param, param],
+        start_time: Uint128::from(start_time),
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let msg = ExecuteMsg::AddUser {
+        project_id: Uint64::new(1),
+        stage: Uint128::zero(),
+        wallet: Addr::unchecked("seed1"),
+        amount: Uint128::new(100),
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::TransferUser {
+        project_id: Uint64::new(1),
+        stage: Uint128::zero(),
+        from: Addr::unchecked("seed1"),
+        to: Addr::unchecked("seed2"),
+        amount: Uint128::new(40),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new(EVENT_VESTING_USER_TRANSFERRED)
+            .add_attribute(ATTR_PROJECT_ID, "1")
+            .add_attribute(ATTR_STAGE, "0")
+            .add_attribute(ATTR_WALLET, "seed1")
+            .add_attribute(ATTR_RECIPIENT, "seed2")
+            .add_attribute(ATTR_AMOUNT, "40")]
+    );
+
+    //fully unlocked, in token decimals
+    deps.querier.with_token_balances(&[(
+        &String::from("WeFund"),
+        &[(&String::from(MOCK_CONTRACT_ADDR), &Uint128::new(1_000_000_000))],
+    )]);
+    let msg = ExecuteMsg::ClaimPendingTokens {
+        project_id: Uint64::new(1),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("seed2", &[]), msg).unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new(EVENT_VESTING_CLAIM)
+            .add_attribute(ATTR_PROJECT_ID, "1")
+            .add_attribute(ATTR_WALLET, "seed2")
+            .add_attribute(ATTR_AMOUNT, "40000000")]
+    );
+}
@@ -1,36 +1,74 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-   to_binary, Addr, AllBalanceResponse, BalanceResponse, BankMsg, BankQuery, Coin, CosmosMsg,
-   DepsMut, Env, MessageInfo, QueryRequest, Response, StdResult, SubMsg, Uint128, Uint64, WasmMsg,
+   from_binary, to_binary, to_vec, Addr, AllBalanceResponse, BalanceResponse, BankMsg, BankQuery,
+   Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order,
+   QueryRequest, Response, StdError, StdResult, Storage, SubMsg, Uint128, Uint64, WasmMsg,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw20::{
-   BalanceResponse as Cw20BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, TokenInfoResponse,
+   BalanceResponse as Cw20BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg,
+   Expiration, TokenInfoResponse,
 };
+use cw_storage_plus::Bound;
 
 use crate::error::ContractError;
 use crate::state::{
+   held_positions,
+   positions,
+   projectstates,
+   save_position,
    save_projectstate,
+   ACCEPTED_DENOMS,
+   APPROVAL_VOTERS,
+   APPROVAL_VOTES,
+   ATTESTATIONS,
+   BACKERS,
+   BONDS,
    COMMUNITY,
    CONFIG,
+   CW20_TOKENS,
+   LOTTERIES,
+   MERKLE_ROOTS,
+   MERKLE_WHITELIST,
+   MILESTONES,
+   OPERATORS,
    PROFIT,
-   PROJECTSTATES,
    PROJECT_SEQ,
+   PUBLIC_BACKED,
+   RELAYER_KEYS,
+   ROLES,
+   VOTES,
+   WHITELIST,
+   WHITELIST_COMMUNITY,
+   WHITELIST_RECHECKED,
    // UUSD_AMOUNT,AUST_AMOUNT, PROJECT_ID,
 };
 use Interface::wefund::{
-   BackerState, Config, ExecuteMsg, InstantiateMsg, Milestone, ProjectState, ProjectStatus,
-   TeamMember, VestingParameter, Vote, WhitelistState,
+   AcceptedDenom, AllocationPreviewResponse, ApprovalTallyResponse, Attestation, BackerState,
+   BondState, BondStatus, Config, ExecuteMsg, FeeRecipient, InstantiateMsg, LotterySlots,
+   LotteryState, MigrateMsg, Milestone, MilestoneTallyResponse, Period, Position, ProjectInfo,
+   ProjectState, ProjectStatus, PublicSale, ReceiveMsg, Role, TierWeights, Vote, VoteOption,
+   WhitelistProof, WhitelistState,
 };
 
-use Interface::staking::CardType;
+use crate::migrate::{migrate_config_v18, migrate_projects_v18};
+use sha2::{Digest, Sha256};
+use Interface::cw721::{Approval, Cw721ReceiveMsg};
+use Interface::events::*;
+use Interface::merkle::{leaf_hash, verify_proof};
+use Interface::staking::{CardType, QueryMsg as StakingQueryMsg, UserInfo as StakingUserInfo};
+use Interface::version::parse_version;
 use Interface::vesting::{ExecuteMsg as VestingMsg, VestingParameter as VestingParam};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "WEFUND";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
-pub const UST: u128 = 1_000_000; //ust unit
+pub const FEE_BPS_DENOMINATOR: u64 = 10_000;
+//-----------lottery secrets are due a day after both commitments---------
+pub const LOTTERY_REVEAL_PERIOD: u64 = 24 * 60 * 60;
+//-----------staking queries per whitelist recheck batch-------------------
+pub const RECHECK_LIMIT: u32 = 30;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -62,17 +100,46 @@ pub fn instantiate(
       .and_then(|s| deps.api.addr_validate(s.as_str()).ok())
       .unwrap_or(Addr::unchecked("".to_string()));
 
+   let staking_contract = msg
+      .staking_contract
+      .and_then(|s| deps.api.addr_validate(s.as_str()).ok())
+      .unwrap_or(Addr::unchecked("".to_string()));
+
+   let fee_bps = check_fee_bps(msg.fee_bps.unwrap_or(Uint64::new(500)))?; //5% by default
+   let fee_recipients =
+      check_fee_recipients(deps.as_ref(), msg.fee_recipients.unwrap_or_default())?;
+
+   //-----------no submit bond by default-------------------------
+   let bond_amount = msg.bond_amount.unwrap_or_default();
+   let bond_token = msg
+      .bond_token
+      .and_then(|s| deps.api.addr_validate(s.as_str()).ok())
+      .unwrap_or(Addr::unchecked("".to_string()));
+   let bond_token_amount = msg.bond_token_amount.unwrap_or_default();
+   let bond_slash_bps = check_fee_bps(msg.bond_slash_bps.unwrap_or_default())?;
+
    let config = Config {
       owner,
       wefund,
       denom,
       decimals,
       vesting_contract,
+      staking_contract,
+      fee_bps,
+      fee_recipients,
+      bond_amount,
+      bond_token,
+      bond_token_amount,
+      bond_slash_bps,
+      approval_quorum: Decimal::percent(50),
+      approval_threshold: Decimal::percent(50),
+      approval_vote_period: Uint64::new(7 * 24 * 60 * 60), //a week
    };
 
    CONFIG.save(deps.storage, &config)?;
    PROJECT_SEQ.save(deps.storage, &Uint64::zero())?;
    COMMUNITY.save(deps.storage, &Vec::new())?;
+   RELAYER_KEYS.save(deps.storage, &Vec::new())?;
 
    // AUST_AMOUNT.save(deps.storage, &Uint128::zero())?;
    // UUSD_AMOUNT.save(deps.storage, &Uint128::zero())?;
@@ -83,6 +150,34 @@ pub fn instantiate(
    Ok(Response::new().add_attribute("method", "instantiate"))
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+   let stored = get_contract_version(deps.storage)?;
+   if stored.contract != CONTRACT_NAME {
+      return Err(ContractError::InvalidContractName {
+         name: stored.contract,
+      });
+   }
+   let version = parse_version(&stored.version)?;
+   if version > parse_version(CONTRACT_VERSION)? {
+      return Err(ContractError::CannotDowngrade {
+         version: stored.version,
+      });
+   }
+
+   //-----------state transforms, in version order---------------------
+   if version < parse_version("18.0.0")? {
+      migrate_config_v18(deps.branch())?;
+      migrate_projects_v18(deps.branch(), &env)?;
+   }
+
+   set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+   Ok(Response::new()
+      .add_attribute("action", "migrate")
+      .add_attribute("from_version", stored.version)
+      .add_attribute("to_version", CONTRACT_VERSION))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
    deps: DepsMut,
@@ -97,90 +192,49 @@ pub fn execute(
          denom,
          decimals,
          vesting_contract,
+         staking_contract,
+         fee_bps,
+         fee_recipients,
+         bond_amount,
+         bond_token,
+         bond_token_amount,
+         bond_slash_bps,
       } => try_setconfig(
          deps,
          _env,
          info,
-         admin,
-         wefund,
-         denom,
-         decimals,
-         vesting_contract,
+         ConfigParams {
+            admin,
+            wefund,
+            denom,
+            decimals,
+            vesting_contract,
+            staking_contract,
+            fee_bps,
+            fee_recipients,
+            bond_amount,
+            bond_token,
+            bond_token_amount,
+            bond_slash_bps,
+         },
       ),
-      ExecuteMsg::AddProject {
-         project_id,
-         project_company,
-         project_title,
-         project_description,
-         project_ecosystem,
-         project_fundtype,
-         project_createddate,
-         project_saft,
-         project_logo,
-         project_whitepaper,
-         project_website,
-         project_email,
-         creator_wallet,
-         project_collected,
-         project_milestones,
-         project_teammembers,
-         vesting,
-         token_addr,
-         country,
-         cofounder_name,
-         service_wefund,
-         service_charity,
-         professional_link,
-      } => try_addproject(
-         deps,
-         _env,
-         info,
+      ExecuteMsg::SubmitProject { project_info } => {
+         try_submitproject(deps, _env, info, project_info)
+      }
+      ExecuteMsg::UpdateProject {
          project_id,
-         project_company,
-         project_title,
-         project_description,
-         project_ecosystem,
-         project_fundtype,
-         project_createddate,
-         project_saft,
-         project_logo,
-         project_whitepaper,
-         project_website,
-         project_email,
-         creator_wallet,
-         project_collected,
-         project_milestones,
-         project_teammembers,
-         vesting,
-         token_addr,
-         country,
-         cofounder_name,
-         service_wefund,
-         service_charity,
-         professional_link,
-      ),
+         project_info,
+      } => try_updateproject(deps, _env, info, project_id, project_info),
       ExecuteMsg::Back2ProjectWithout {
-         project_id,
-         backer_wallet,
-         denom,
-         amount,
-         fundraising_stage,
-         token_amount,
-         otherchain,
-         otherchain_wallet,
-      } => try_back2projectwithout(
-         deps,
-         _env,
-         info,
-         project_id,
-         backer_wallet,
-         denom,
-         amount,
-         fundraising_stage,
-         token_amount,
-         otherchain,
-         otherchain_wallet,
-      ),
+         attestation,
+         pubkey,
+         signature,
+      } => try_back2projectwithout(deps, _env, info, attestation, pubkey, signature),
+      ExecuteMsg::AddRelayerKey { pubkey } => try_addrelayerkey(deps, info, pubkey),
+      ExecuteMsg::SetMerkleRoot { project_id, root } => {
+         try_setmerkleroot(deps, info, project_id, root)
+      }
+      ExecuteMsg::RemoveRelayerKey { pubkey } => try_removerelayerkey(deps, info, pubkey),
       ExecuteMsg::Back2Project {
          project_id,
          backer_wallet,
@@ -188,43 +242,127 @@ pub fn execute(
          token_amount,
          otherchain,
          otherchain_wallet,
+         whitelist_proof,
       } => try_back2project(
          deps,
          _env,
          info,
-         project_id,
-         backer_wallet,
-         fundraising_stage,
-         token_amount,
-         otherchain,
-         otherchain_wallet,
+         BackParams {
+            project_id,
+            backer_wallet,
+            fundraising_stage,
+            token_amount,
+            otherchain,
+            otherchain_wallet,
+            whitelist_proof,
+         },
       ),
 
-      ExecuteMsg::CompleteProject { project_id } => try_completeproject(deps, _env, project_id),
+      ExecuteMsg::Receive(cw20_msg) => try_receive(deps, _env, info, cw20_msg),
+
+      ExecuteMsg::CompleteProject { project_id } => {
+         try_completeproject(deps, _env, info, project_id)
+      }
+
+      ExecuteMsg::FinalizeFundraising { project_id } => {
+         try_finalizefundraising(deps, _env, project_id)
+      }
+      ExecuteMsg::OpenPublicSale { project_id } => try_openpublicsale(deps, _env, project_id),
+
+      ExecuteMsg::FailProject { project_id } => try_failproject(deps, _env, info, project_id),
 
-      ExecuteMsg::FailProject { project_id } => try_failproject(deps, _env, project_id),
+      ExecuteMsg::ClaimRefund { project_id } => try_claimrefund(deps, _env, info, project_id),
 
       ExecuteMsg::RemoveProject { project_id } => try_removeproject(deps, info, project_id),
 
       ExecuteMsg::TransferAllCoins { wallet } => try_transferallcoins(deps, _env, info, wallet),
 
-      ExecuteMsg::AddCommunitymember { wallet } => try_addcommunitymember(deps, wallet),
+      ExecuteMsg::AddCommunitymember { wallet } => try_addcommunitymember(deps, info, wallet),
+
+      ExecuteMsg::SetAcceptedDenom {
+         denom,
+         is_cw20,
+         rate,
+      } => try_setaccepteddenom(deps, info, denom, is_cw20, rate),
+
+      ExecuteMsg::RemoveAcceptedDenom { denom } => try_removeaccepteddenom(deps, info, denom),
+
+      ExecuteMsg::RemoveCommunitymember { wallet } => try_removecommunitymember(deps, info, wallet),
+
+      ExecuteMsg::ProposeProject {
+         project_id,
+         fee_bps,
+      } => try_proposeproject(deps, _env, info, project_id, fee_bps),
+
+      ExecuteMsg::SetApprovalVote { project_id, option } => {
+         try_setapprovalvote(deps, _env, info, project_id, option)
+      }
+
+      ExecuteMsg::TallyApproval { project_id } => try_tallyapproval(deps, _env, project_id),
 
-      ExecuteMsg::RemoveCommunitymember { wallet } => try_removecommunitymember(deps, wallet),
+      ExecuteMsg::VetoProject { project_id, reason } => {
+         try_vetoproject(deps, info, project_id, reason)
+      }
 
-      ExecuteMsg::WefundApprove { project_id } => try_wefundapprove(deps, info, project_id),
+      ExecuteMsg::SetApprovalConfig {
+         quorum,
+         threshold,
+         vote_period,
+      } => try_setapprovalconfig(deps, info, quorum, threshold, vote_period),
+
+      ExecuteMsg::TransferNft {
+         recipient,
+         token_id,
+      } => try_transfernft(deps, _env, info, recipient, token_id),
+      ExecuteMsg::SendNft {
+         contract,
+         token_id,
+         msg,
+      } => try_sendnft(deps, _env, info, contract, token_id, msg),
+      ExecuteMsg::Approve {
+         spender,
+         token_id,
+         expires,
+      } => try_approve(deps, _env, info, spender, token_id, expires),
+      ExecuteMsg::Revoke { spender, token_id } => try_revoke(deps, _env, info, spender, token_id),
+      ExecuteMsg::ApproveAll { operator, expires } => {
+         try_approveall(deps, _env, info, operator, expires)
+      }
+      ExecuteMsg::RevokeAll { operator } => try_revokeall(deps, info, operator),
 
       ExecuteMsg::SetFundraisingStage { project_id, stage } => {
-         try_setfundraisingstage(deps, project_id, stage)
+         try_setfundraisingstage(deps, info, project_id, stage)
+      }
+
+      ExecuteMsg::SetMilestoneVote { project_id, option } => {
+         try_setmilestonevote(deps, _env, info, project_id, option)
       }
 
-      ExecuteMsg::SetMilestoneVote {
+      ExecuteMsg::SetVotingConfig {
+         project_id,
+         quorum,
+         threshold,
+         vote_period,
+         max_rejections,
+      } => try_setvotingconfig(
+         deps,
+         info,
          project_id,
-         wallet,
-         voted,
-      } => try_setmilestonevote(deps, _env, info, project_id, wallet, voted),
+         quorum,
+         threshold,
+         vote_period,
+         max_rejections,
+      ),
+
+      ExecuteMsg::TallyMilestone { project_id } => try_tallymilestone(deps, _env, project_id),
 
-      ExecuteMsg::ReleaseMilestone { project_id } => try_releasemilestone(deps, _env, project_id),
+      ExecuteMsg::ReopenMilestoneVote { project_id } => {
+         try_reopenmilestonevote(deps, _env, info, project_id)
+      }
+
+      ExecuteMsg::ReleaseMilestone { project_id } => {
+         try_releasemilestone(deps, _env, info, project_id)
+      }
 
       ExecuteMsg::SetProjectStatus { project_id, status } => {
          try_setprojectstatus(deps, info, project_id, status)
@@ -233,49 +371,115 @@ pub fn execute(
       ExecuteMsg::OpenWhitelist {
          project_id,
          holder_alloc,
-      } => try_openwhitelist(deps, _env, info, project_id, holder_alloc),
-
-      ExecuteMsg::RegisterWhitelist {
+         tier_weights,
+         lottery,
+         whitelist_period,
+         fundraising_period,
+         public_sale,
+      } => try_openwhitelist(
+         deps,
+         _env,
+         info,
          project_id,
-         card_type,
-      } => try_registerwhitelist(deps, _env, info, project_id, card_type),
+         WhitelistParams {
+            holder_alloc,
+            tier_weights,
+            lottery,
+            whitelist_period,
+            fundraising_period,
+            public_sale,
+         },
+      ),
+
+      ExecuteMsg::RegisterWhitelist { project_id } => {
+         try_registerwhitelist(deps, _env, info, project_id)
+      }
+
+      ExecuteMsg::RecheckWhitelist { project_id, limit } => {
+         try_recheckwhitelist(deps, project_id, limit)
+      }
 
       ExecuteMsg::CloseWhitelist { project_id } => try_closewhitelist(deps, _env, info, project_id),
+
+      ExecuteMsg::CommitLotterySeed {
+         project_id,
+         commitment,
+      } => try_commitlotteryseed(deps, _env, info, project_id, commitment),
+
+      ExecuteMsg::RevealLotterySeed { project_id, secret } => {
+         try_reveallotteryseed(deps, info, project_id, secret)
+      }
+
+      ExecuteMsg::GrantRole { wallet, role } => try_grantrole(deps, info, wallet, role),
+
+      ExecuteMsg::RevokeRole { wallet, role } => try_revokerole(deps, info, wallet, role),
    }
 }
 pub fn try_setprojectstatus(
    deps: DepsMut,
    info: MessageInfo,
    project_id: Uint64,
-   status: Uint128,
+   status: ProjectStatus,
 ) -> Result<Response, ContractError> {
-   //-----------check owner--------------------------
-   let config = CONFIG.load(deps.storage).unwrap();
-   if info.sender != config.owner {
-      return Err(ContractError::Unauthorized {});
+   //-----------check admin--------------------------
+   check_role(deps.storage, &info.sender, Role::Admin)?;
+   let mut x: ProjectState = projectstates().load(deps.storage, project_id.u64())?;
+   //-------approval is up to community vote or owner veto-----------
+   if x.project_status == ProjectStatus::WefundVote {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   //-------forward steps have side effects, they go through their own messages--
+   if !matches!(
+      status,
+      ProjectStatus::Fail | ProjectStatus::Done | ProjectStatus::Rejected
+   ) {
+      return Err(ContractError::NotAllowedTransition {
+         from: x.project_status,
+         to: status,
+      });
    }
-   //    let x:ProjectState = PROJECTSTATES.load(deps.storage, _project_id.u64())?;
+   let event = transition_project(&mut x, status)?;
    //-------update-------------------------
-   PROJECTSTATES.update(deps.storage, project_id.u64(), |op| match op {
-      None => Err(ContractError::NotRegisteredProject {}),
-      Some(mut project) => {
-         if status == Uint128::zero() {
-            project.project_status = ProjectStatus::WefundVote;
-         } else if status == Uint128::from(1u64) {
-            project.project_status = ProjectStatus::Whitelist;
-         } else if status == Uint128::from(2u64) {
-            project.project_status = ProjectStatus::Fundraising;
-         } else if status == Uint128::from(3u64) {
-            project.project_status = ProjectStatus::Releasing;
-         } else if status == Uint128::from(4u64) {
-            project.project_status = ProjectStatus::Done;
-         } else if status == Uint128::from(5u64) {
-            project.project_status = ProjectStatus::Fail;
-         }
-         Ok(project)
-      }
-   })?;
-   Ok(Response::new().add_attribute("action", "Set project status"))
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+   Ok(Response::new()
+      .add_event(event)
+      .add_attribute("action", "Set project status"))
+}
+
+//---------project lifecycle, every status change goes through here--------------
+pub fn transition_project(
+   x: &mut ProjectState,
+   status: ProjectStatus,
+) -> Result<Event, ContractError> {
+   let allowed = matches!(
+      (&x.project_status, &status),
+      (ProjectStatus::WefundVote, ProjectStatus::Whitelist)
+         | (ProjectStatus::WefundVote, ProjectStatus::Rejected)
+         | (ProjectStatus::Whitelist, ProjectStatus::Rejected)
+         | (ProjectStatus::Whitelist, ProjectStatus::Fundraising)
+         | (ProjectStatus::Fundraising, ProjectStatus::Releasing)
+         | (ProjectStatus::Fundraising, ProjectStatus::Fail)
+         | (ProjectStatus::Fundraising, ProjectStatus::PublicSale)
+         | (ProjectStatus::PublicSale, ProjectStatus::Releasing)
+         | (ProjectStatus::PublicSale, ProjectStatus::Fail)
+         | (ProjectStatus::Releasing, ProjectStatus::Done)
+         | (ProjectStatus::Releasing, ProjectStatus::Fail)
+   );
+   if !allowed {
+      return Err(ContractError::NotAllowedTransition {
+         from: x.project_status.clone(),
+         to: status,
+      });
+   }
+
+   let event = Event::new(EVENT_PROJECT_STATUS)
+      .add_attribute(ATTR_PROJECT_ID, x.project_id.to_string())
+      .add_attribute(ATTR_OLD_STATUS, format!("{:?}", x.project_status))
+      .add_attribute(ATTR_NEW_STATUS, format!("{:?}", status));
+   x.project_status = status;
+   Ok(event)
 }
 pub fn convert_str_int(str: String) -> u128 {
    let bytes = str.into_bytes();
@@ -296,12 +500,23 @@ pub fn convert_str_int(str: String) -> u128 {
    return res;
 }
 pub fn try_releasemilestone(
+   deps: DepsMut,
+   _env: Env,
+   info: MessageInfo,
+   project_id: Uint64,
+) -> Result<Response, ContractError> {
+   check_role(deps.storage, &info.sender, Role::Operator)?;
+   release_milestone(deps, _env, project_id)
+}
+
+//---------release current milestone, after vote passed or by operator----------
+fn release_milestone(
    deps: DepsMut,
    _env: Env,
    _project_id: Uint64,
 ) -> Result<Response, ContractError> {
    //--------Get project info----------------------------
-   let mut x: ProjectState = PROJECTSTATES.load(deps.storage, _project_id.u64())?;
+   let mut x: ProjectState = projectstates().load(deps.storage, _project_id.u64())?;
 
    //--------Checking project status-------------------------
    if x.project_status != ProjectStatus::Releasing {
@@ -313,28 +528,37 @@ pub fn try_releasemilestone(
 
    //---------get hope to release amount---------------------------
    let config = CONFIG.load(deps.storage).unwrap();
-   let step = x.project_milestonestep.u128() as usize;
-   let release_amount =
-      x.milestone_states[step].milestone_amount.u128() * (100u128).pow(config.decimals);
-
-   let coin = Coin::new(release_amount, config.denom);
-   let send2_creator = BankMsg::Send {
-      to_address: x.creator_wallet.to_string(),
-      amount: vec![coin],
-   };
+   let step = x.project_milestonestep.u128() as u64;
+   let mut milestone = MILESTONES.load(deps.storage, (_project_id.u64(), step))?;
+   let release_amount = milestone.milestone_amount.u128() * (10u128).pow(config.decimals);
+   let escrow_amount = escrow_share(&x, Uint128::from(release_amount));
+
+   let mut msgs: Vec<CosmosMsg> = Vec::new();
+   for fund in take_escrow(&mut x, escrow_amount) {
+      msgs.push(transfer_fund(deps.storage, fund, &x.creator_wallet)?);
+   }
 
-   x.milestone_states[step].milestone_status = Uint128::new(2); //switch to released status
+   let mut events: Vec<Event> = vec![Event::new(EVENT_MILESTONE_RELEASED)
+      .add_attribute(ATTR_PROJECT_ID, _project_id.to_string())
+      .add_attribute(ATTR_STEP, step.to_string())
+      .add_attribute(ATTR_AMOUNT, release_amount.to_string())];
+   milestone.milestone_status = Uint128::new(2); //switch to released status
+   MILESTONES.save(deps.storage, (_project_id.u64(), step), &milestone)?;
    x.project_milestonestep += Uint128::new(1); //switch to next milestone step
                                                //-----------check milestone done---------------------
-   if x.project_milestonestep >= Uint128::new(x.milestone_states.len() as u128) {
-      x.project_status = ProjectStatus::Done; //switch to project done status
+   if x.project_milestonestep >= Uint128::from(x.milestone_count.u64()) {
+      events.push(transition_project(&mut x, ProjectStatus::Done)?); //switch to project done status
+   } else {
+      //------open voting for next milestone, rejections count per milestone------
+      open_milestone_vote(&mut x, &_env);
+      x.milestone_rejections = Uint64::zero();
    }
 
-   x.backerbacked_amount -= Uint128::from(release_amount);
-   PROJECTSTATES.save(deps.storage, _project_id.u64(), &x)?;
+   projectstates().save(deps.storage, _project_id.u64(), &x)?;
 
    Ok(Response::new()
-      .add_message(send2_creator)
+      .add_messages(msgs)
+      .add_events(events)
       .add_attribute("action", "release milestone"))
 }
 pub fn try_setmilestonevote(
@@ -342,10 +566,9 @@ pub fn try_setmilestonevote(
    _env: Env,
    info: MessageInfo,
    project_id: Uint64,
-   wallet: String,
-   voted: bool,
+   option: VoteOption,
 ) -> Result<Response, ContractError> {
-   let mut x: ProjectState = PROJECTSTATES.load(deps.storage, project_id.u64())?;
+   let mut x: ProjectState = projectstates().load(deps.storage, project_id.u64())?;
    //-------check project status-------------------
    if x.project_status != ProjectStatus::Releasing {
       //only releasing status
@@ -354,675 +577,2553 @@ pub fn try_setmilestonevote(
       });
    }
 
-   let wallet = deps.api.addr_validate(&wallet).unwrap();
-   let step = x.project_milestonestep.u128() as usize;
+   //------votes are cast by backer wallet itself------
+   let wallet = info.sender;
+   let step = x.project_milestonestep.u128() as u64;
+   let mut milestone = MILESTONES.load(deps.storage, (project_id.u64(), step))?;
 
-   if x.milestone_states[step].milestone_status != Uint128::zero() {
+   if milestone.milestone_status != Uint128::zero() {
       //only voting status
       return Err(ContractError::NotCorrectMilestoneStatus {
-         step: step,
-         status: x.milestone_states[step].milestone_status,
+         step: step as usize,
+         status: milestone.milestone_status,
       });
    }
 
-   //------set vot status and check all voted for same backer--------------------
-   let mut all_voted = true;
-   for vote in x.milestone_states[step].milestone_votes.iter_mut() {
-      if vote.wallet == wallet {
-         vote.voted = voted;
-      }
-      all_voted = all_voted & vote.voted;
+   if _env.block.time.seconds() >= x.milestone_vote_end.u64() {
+      return Err(ContractError::VotingEnded {});
    }
 
-   let mut deps = deps;
-   if all_voted {
-      x.milestone_states[step].milestone_status = Uint128::new(1); //switch to releasing status
-
-      //-------update-------------------------
-      PROJECTSTATES.update(deps.storage, project_id.u64(), |op| match op {
-         None => Err(ContractError::NotRegisteredProject {}),
-         Some(mut project) => {
-            project.milestone_states = x.milestone_states;
-            Ok(project)
-         }
-      })?;
+   //------holders vote with weight of positions they hold------
+   let weight = claim_vote_weight(deps.storage, &x, &wallet)?;
+   if weight.is_zero() {
+      return Err(ContractError::NotBackerWallet {});
+   }
 
-      return try_releasemilestone(deps.branch(), _env, project_id);
+   //------replace previous vote of this round in tally--------------------
+   if let Some(prev) = VOTES.may_load(deps.storage, (project_id.u64(), &wallet))? {
+      if prev.round == x.milestone_vote_round {
+         tally_vote(&mut x, &prev, false);
+      }
+   }
+   let vote = Vote {
+      wallet: wallet.clone(),
+      weight,
+      option,
+      round: x.milestone_vote_round,
+   };
+   tally_vote(&mut x, &vote, true);
+   VOTES.save(deps.storage, (project_id.u64(), &wallet), &vote)?;
+   let event = Event::new(EVENT_VOTE_CAST)
+      .add_attribute(ATTR_PROJECT_ID, project_id.to_string())
+      .add_attribute(ATTR_KIND, VOTE_KIND_MILESTONE)
+      .add_attribute(ATTR_WALLET, wallet.to_string())
+      .add_attribute(ATTR_OPTION, format!("{:?}", vote.option))
+      .add_attribute(ATTR_WEIGHT, vote.weight.to_string())
+      .add_attribute(ATTR_ROUND, vote.round.to_string());
+
+   if calc_milestone_tally(&x).passed {
+      milestone.milestone_status = Uint128::new(1); //switch to releasing status
+      MILESTONES.save(deps.storage, (project_id.u64(), step), &milestone)?;
+      projectstates().save(deps.storage, project_id.u64(), &x)?;
+
+      return Ok(release_milestone(deps, _env, project_id)?.add_event(event));
    }
    //-------update-------------------------
-   PROJECTSTATES.update(deps.storage, project_id.u64(), |op| match op {
-      None => Err(ContractError::NotRegisteredProject {}),
-      Some(mut project) => {
-         project.milestone_states = x.milestone_states;
-         project.project_milestonestep = x.project_milestonestep;
-         project.project_status = x.project_status;
-         Ok(project)
-      }
-   })?;
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
 
-   Ok(Response::new().add_attribute("action", "Set milestone vote"))
+   Ok(Response::new()
+      .add_event(event)
+      .add_attribute("action", "Set milestone vote"))
 }
 
-pub fn try_setfundraisingstage(
-   deps: DepsMut,
-   project_id: Uint64,
-   stage: Uint128,
-) -> Result<Response, ContractError> {
-   PROJECTSTATES.update(deps.storage, project_id.u64(), |op| match op {
-      None => Err(ContractError::NotRegisteredProject {}),
-      Some(mut project) => {
-         project.fundraising_stage = stage;
-         Ok(project)
+//---------weight of held positions, counted for holder's vote in this round---------
+pub fn claim_vote_weight(
+   store: &mut dyn Storage,
+   x: &ProjectState,
+   wallet: &Addr,
+) -> StdResult<Uint128> {
+   let mut weight = Uint128::zero();
+   for mut position in held_positions(store, x.project_id, wallet)? {
+      if position.weight.is_zero() {
+         continue;
       }
-   })?;
+      weight += position.weight;
+      position.voted_round = x.milestone_vote_round;
+      positions().save(store, position.token_id.u64(), &position)?;
+   }
+   Ok(weight)
+}
 
-   Ok(Response::new().add_attribute("action", "Set Fundraising stage"))
+//---------cw721 token id is the position id------------------------------------
+fn parse_token_id(token_id: &str) -> Result<u64, ContractError> {
+   token_id
+      .parse::<u64>()
+      .map_err(|_| ContractError::InvalidTokenId {
+         token_id: token_id.to_string(),
+      })
 }
 
-pub fn try_wefundapprove(
-   deps: DepsMut,
-   info: MessageInfo,
-   project_id: Uint64,
-) -> Result<Response, ContractError> {
-   //-----------check owner--------------------------
-   let config = CONFIG.load(deps.storage).unwrap();
-   if info.sender != config.owner {
-      return Err(ContractError::Unauthorized {});
-   }
+//---------operator approved by owner for all positions, until expiry-----------
+fn is_operator(deps: Deps, env: &Env, owner: &Addr, sender: &Addr) -> StdResult<bool> {
+   let expires = OPERATORS.may_load(deps.storage, (owner, sender))?;
+   Ok(matches!(expires, Some(x) if !x.is_expired(&env.block)))
+}
 
-   let mut x: ProjectState = PROJECTSTATES.load(deps.storage, project_id.u64())?;
-   //-------check project status-------------------
-   if x.project_status != ProjectStatus::WefundVote {
-      //only wefund approve status
-      return Err(ContractError::NotCorrectStatus {
-         status: x.project_status as u32,
-      });
+fn check_can_approve(
+   deps: Deps,
+   env: &Env,
+   sender: &Addr,
+   position: &Position,
+) -> Result<(), ContractError> {
+   if position.owner == *sender || is_operator(deps, env, &position.owner, sender)? {
+      return Ok(());
    }
-   x.project_status = ProjectStatus::Whitelist; //switch to fundraising status
-
-   PROJECTSTATES.update(deps.storage, project_id.u64(), |op| match op {
-      None => Err(ContractError::NotRegisteredProject {}),
-      Some(mut project) => {
-         project.project_status = x.project_status;
-         Ok(project)
-      }
-   })?;
-
-   Ok(Response::new().add_attribute("action", "Wefund Approve"))
+   Err(ContractError::Unauthorized {})
 }
 
-pub fn try_removecommunitymember(deps: DepsMut, wallet: String) -> Result<Response, ContractError> {
-   let wallet = deps.api.addr_validate(&wallet).unwrap();
-
-   let mut community = COMMUNITY.load(deps.storage).unwrap();
-   let res = community.iter().find(|&x| x == &wallet);
-   if res == None {
-      return Err(ContractError::NotRegisteredCommunity {});
+fn check_can_send(
+   deps: Deps,
+   env: &Env,
+   sender: &Addr,
+   position: &Position,
+) -> Result<(), ContractError> {
+   let approved = position
+      .approvals
+      .iter()
+      .any(|x| x.spender == sender.as_str() && !x.expires.is_expired(&env.block));
+   if approved {
+      return Ok(());
    }
-
-   community.retain(|x| x != &wallet);
-   COMMUNITY.save(deps.storage, &community)?;
-
-   Ok(Response::new().add_attribute("action", "remove community member"))
+   check_can_approve(deps, env, sender, position)
 }
 
-pub fn try_addcommunitymember(deps: DepsMut, wallet: String) -> Result<Response, ContractError> {
-   let wallet = deps.api.addr_validate(&wallet).unwrap();
-
-   let mut community = COMMUNITY.load(deps.storage).unwrap();
-   let res = community.iter().find(|&x| x == &wallet);
-   if res != None {
-      return Err(ContractError::AlreadyRegisteredCommunity {});
+//---------move position to recipient with its vote weight and vested tokens-----
+fn transfer_position(
+   deps: DepsMut,
+   env: &Env,
+   sender: &Addr,
+   token_id: &str,
+   recipient: &Addr,
+) -> Result<Response, ContractError> {
+   let id = parse_token_id(token_id)?;
+   let mut position = positions().load(deps.storage, id)?;
+   check_can_send(deps.as_ref(), env, sender, &position)?;
+   let owner = position.owner.clone();
+   let project_id = position.project_id.u64();
+   let mut x: ProjectState = projectstates().load(deps.storage, project_id)?;
+
+   //------weight leaves the running vote of the owner with the position------
+   if x.project_status == ProjectStatus::Releasing
+      && position.voted_round == x.milestone_vote_round
+      && !position.weight.is_zero()
+   {
+      if let Some(mut vote) = VOTES.may_load(deps.storage, (project_id, &owner))? {
+         if vote.round == x.milestone_vote_round {
+            tally_vote(&mut x, &vote, false);
+            vote.weight -= position.weight;
+            tally_vote(&mut x, &vote, true);
+            VOTES.save(deps.storage, (project_id, &owner), &vote)?;
+            projectstates().save(deps.storage, project_id, &x)?;
+         }
+      }
    }
+   position.voted_round = Uint64::zero();
+   position.owner = recipient.clone();
+   position.approvals = Vec::new();
+   positions().save(deps.storage, id, &position)?;
 
-   community.push(wallet);
-   COMMUNITY.save(deps.storage, &community)?;
+   //------vested tokens follow the holder------
+   let config = CONFIG.load(deps.storage)?;
+   let mut msgs: Vec<CosmosMsg> = Vec::new();
+   if config.vesting_contract != "".to_string() && !position.token_amount.is_zero() {
+      msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+         contract_addr: config.vesting_contract.to_string(),
+         msg: to_binary(&VestingMsg::TransferUser {
+            project_id: position.project_id,
+            stage: position.stage,
+            from: owner.clone(),
+            to: recipient.clone(),
+            amount: position.token_amount,
+         })?,
+         funds: vec![],
+      }));
+   }
 
-   Ok(Response::new().add_attribute("action", "add community member"))
+   Ok(Response::new()
+      .add_messages(msgs)
+      .add_event(
+         Event::new(EVENT_POSITION_TRANSFERRED)
+            .add_attribute(ATTR_PROJECT_ID, position.project_id.to_string())
+            .add_attribute(ATTR_TOKEN_ID, token_id)
+            .add_attribute(ATTR_WALLET, owner.to_string())
+            .add_attribute(ATTR_RECIPIENT, recipient.to_string()),
+      )
+      .add_attribute("token_id", token_id)
+      .add_attribute("sender", sender.to_string())
+      .add_attribute("recipient", recipient.to_string()))
 }
-pub fn try_transferallcoins(
+
+pub fn try_transfernft(
    deps: DepsMut,
-   _env: Env,
+   env: Env,
    info: MessageInfo,
-   wallet: String,
+   recipient: String,
+   token_id: String,
 ) -> Result<Response, ContractError> {
-   //-----------check owner--------------------------
-   let config = CONFIG.load(deps.storage).unwrap();
-   if info.sender != config.owner {
-      return Err(ContractError::Unauthorized {});
-   }
-   //--------get all native coins and ust - 4 ----------------------
-   let balance: AllBalanceResponse =
-      deps
-         .querier
-         .query(&QueryRequest::Bank(BankQuery::AllBalances {
-            address: _env.contract.address.to_string(),
-         }))?;
+   let recipient = deps.api.addr_validate(&recipient)?;
+   let res = transfer_position(deps, &env, &info.sender, &token_id, &recipient)?;
+   Ok(res.add_attribute("action", "transfer_nft"))
+}
 
-   let bank_native = BankMsg::Send {
-      to_address: wallet.clone(),
-      amount: balance.amount,
+pub fn try_sendnft(
+   deps: DepsMut,
+   env: Env,
+   info: MessageInfo,
+   contract: String,
+   token_id: String,
+   msg: Binary,
+) -> Result<Response, ContractError> {
+   let contract = deps.api.addr_validate(&contract)?;
+   let res = transfer_position(deps, &env, &info.sender, &token_id, &contract)?;
+
+   //------receiving contract is told after the position is its own------
+   let receive = Cw721ReceiveMsg {
+      sender: info.sender.to_string(),
+      token_id,
+      msg,
    };
-
-   Ok(Response::new()
-      .add_message(CosmosMsg::Bank(bank_native))
-      .add_attribute("action", "trasnfer all coins"))
+   Ok(res
+      .add_message(receive.into_cosmos_msg(contract)?)
+      .add_attribute("action", "send_nft"))
 }
-pub fn try_removeproject(
+
+pub fn try_approve(
    deps: DepsMut,
+   env: Env,
    info: MessageInfo,
-   project_id: Uint64,
+   spender: String,
+   token_id: String,
+   expires: Option<Expiration>,
 ) -> Result<Response, ContractError> {
-   //-----------check owner--------------------------
-   let config = CONFIG.load(deps.storage).unwrap();
-   if info.sender != config.owner {
-      return Err(ContractError::Unauthorized {});
-   }
-   let res = PROJECTSTATES.may_load(deps.storage, project_id.u64());
-   if res == Ok(None) {
-      return Err(ContractError::NotRegisteredProject {});
+   let id = parse_token_id(&token_id)?;
+   let mut position = positions().load(deps.storage, id)?;
+   check_can_approve(deps.as_ref(), &env, &info.sender, &position)?;
+
+   let spender = deps.api.addr_validate(&spender)?;
+   let expires = expires.unwrap_or_default();
+   if expires.is_expired(&env.block) {
+      return Err(ContractError::Expired {});
    }
-   PROJECTSTATES.remove(deps.storage, project_id.u64());
+   position.approvals.retain(|x| x.spender != spender.as_str());
+   position.approvals.push(Approval {
+      spender: spender.to_string(),
+      expires,
+   });
+   positions().save(deps.storage, id, &position)?;
 
-   Ok(Response::new())
+   Ok(Response::new()
+      .add_attribute("action", "approve")
+      .add_attribute("token_id", token_id)
+      .add_attribute("spender", spender))
 }
 
-pub fn try_setconfig(
+pub fn try_revoke(
    deps: DepsMut,
-   _env: Env,
+   env: Env,
    info: MessageInfo,
-   admin: Option<String>,
-   wefund: Option<String>,
-   denom: Option<String>,
-   decimals: Option<Uint64>,
-   vesting_contract: Option<String>,
+   spender: String,
+   token_id: String,
 ) -> Result<Response, ContractError> {
-   //-----------check owner--------------------------
-   let config = CONFIG.load(deps.storage).unwrap();
-   if info.sender != config.owner {
+   let id = parse_token_id(&token_id)?;
+   let mut position = positions().load(deps.storage, id)?;
+   check_can_approve(deps.as_ref(), &env, &info.sender, &position)?;
+
+   position.approvals.retain(|x| x.spender != spender);
+   positions().save(deps.storage, id, &position)?;
+
+   Ok(Response::new()
+      .add_attribute("action", "revoke")
+      .add_attribute("token_id", token_id)
+      .add_attribute("spender", spender))
+}
+
+pub fn try_approveall(
+   deps: DepsMut,
+   env: Env,
+   info: MessageInfo,
+   operator: String,
+   expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+   let operator = deps.api.addr_validate(&operator)?;
+   let expires = expires.unwrap_or_default();
+   if expires.is_expired(&env.block) {
+      return Err(ContractError::Expired {});
+   }
+   OPERATORS.save(deps.storage, (&info.sender, &operator), &expires)?;
+
+   Ok(Response::new()
+      .add_attribute("action", "approve_all")
+      .add_attribute("operator", operator))
+}
+
+pub fn try_revokeall(
+   deps: DepsMut,
+   info: MessageInfo,
+   operator: String,
+) -> Result<Response, ContractError> {
+   let operator = deps.api.addr_validate(&operator)?;
+   OPERATORS.remove(deps.storage, (&info.sender, &operator));
+
+   Ok(Response::new()
+      .add_attribute("action", "revoke_all")
+      .add_attribute("operator", operator))
+}
+
+//---------add or remove vote weight in running tally---------------------------
+pub fn tally_vote(x: &mut ProjectState, vote: &Vote, add: bool) {
+   let counter = match vote.option {
+      VoteOption::Yes => &mut x.milestone_yes,
+      VoteOption::No => &mut x.milestone_no,
+      VoteOption::Abstain => &mut x.milestone_abstain,
+   };
+   match add {
+      true => *counter += vote.weight,
+      false => *counter -= vote.weight,
+   }
+}
+
+//---------new vote round, votes of previous rounds don't count--------------------
+pub fn open_milestone_vote(x: &mut ProjectState, env: &Env) {
+   x.milestone_vote_round += Uint64::new(1);
+   x.milestone_yes = Uint128::zero();
+   x.milestone_no = Uint128::zero();
+   x.milestone_abstain = Uint128::zero();
+   x.milestone_vote_end = Uint64::new(env.block.time.seconds()) + x.milestone_vote_period;
+}
+
+//---------yes wins once it passes threshold even if the rest votes no------------
+//---------nothing passes without vote weight, operator releases instead----------
+pub fn calc_milestone_tally(x: &ProjectState) -> MilestoneTallyResponse {
+   let yes = x.milestone_yes;
+   let no = x.milestone_no;
+   let abstain = x.milestone_abstain;
+   let total_weight = x.milestone_total_weight;
+
+   let quorum_reached = yes + no + abstain >= total_weight * x.milestone_quorum;
+   let passed =
+      quorum_reached && !yes.is_zero() && yes >= (total_weight - abstain) * x.milestone_threshold;
+
+   MilestoneTallyResponse {
+      milestone_step: x.project_milestonestep,
+      yes,
+      no,
+      abstain,
+      total_weight,
+      quorum: x.milestone_quorum,
+      threshold: x.milestone_threshold,
+      quorum_reached,
+      passed,
+      vote_end: x.milestone_vote_end,
+   }
+}
+
+pub fn try_setvotingconfig(
+   deps: DepsMut,
+   info: MessageInfo,
+   project_id: Uint64,
+   quorum: Decimal,
+   threshold: Decimal,
+   vote_period: Uint64,
+   max_rejections: Uint64,
+) -> Result<Response, ContractError> {
+   //-----------check admin--------------------------
+   check_role(deps.storage, &info.sender, Role::Admin)?;
+   if quorum > Decimal::one() || threshold > Decimal::one() || threshold.is_zero() {
+      return Err(ContractError::InvalidVotingConfig {});
+   }
+   if vote_period.is_zero() || max_rejections.is_zero() {
+      return Err(ContractError::InvalidVotingConfig {});
+   }
+
+   projectstates().update(deps.storage, project_id.u64(), |op| match op {
+      None => Err(ContractError::NotRegisteredProject {}),
+      Some(mut project) => {
+         project.milestone_quorum = quorum;
+         project.milestone_threshold = threshold;
+         project.milestone_vote_period = vote_period;
+         project.milestone_max_rejections = max_rejections;
+         Ok(project)
+      }
+   })?;
+
+   Ok(Response::new().add_attribute("action", "Set voting config"))
+}
+
+pub fn try_tallymilestone(
+   deps: DepsMut,
+   env: Env,
+   project_id: Uint64,
+) -> Result<Response, ContractError> {
+   let mut x: ProjectState = projectstates().load(deps.storage, project_id.u64())?;
+   if x.project_status != ProjectStatus::Releasing {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   let step = x.project_milestonestep.u128() as u64;
+   let mut milestone = MILESTONES.load(deps.storage, (project_id.u64(), step))?;
+   if milestone.milestone_status != Uint128::zero() {
+      return Err(ContractError::NotCorrectMilestoneStatus {
+         step: step as usize,
+         status: milestone.milestone_status,
+      });
+   }
+   //-----------anyone can tally after voting period ended-------------
+   if env.block.time.seconds() < x.milestone_vote_end.u64() {
+      return Err(ContractError::VotingNotEnded {});
+   }
+
+   //-----------at deadline only cast votes count for threshold--------
+   let tally = calc_milestone_tally(&x);
+   let passed = tally.passed
+      || (tally.quorum_reached
+         && !tally.yes.is_zero()
+         && tally.yes >= (tally.yes + tally.no) * tally.threshold);
+
+   if passed {
+      milestone.milestone_status = Uint128::new(1); //switch to releasing status
+      MILESTONES.save(deps.storage, (project_id.u64(), step), &milestone)?;
+      return release_milestone(deps, env, project_id);
+   }
+
+   milestone.milestone_status = Uint128::new(3); //switch to rejected status
+   MILESTONES.save(deps.storage, (project_id.u64(), step), &milestone)?;
+   x.milestone_rejections += Uint64::new(1);
+   let mut events: Vec<Event> = Vec::new();
+   if x.milestone_rejections >= x.milestone_max_rejections {
+      //---------unreleased escrow is refundable on fail status------------
+      events.push(transition_project(&mut x, ProjectStatus::Fail)?);
+   }
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+
+   Ok(Response::new()
+      .add_events(events)
+      .add_attribute("action", "reject milestone")
+      .add_attribute("rejections", x.milestone_rejections.to_string())
+      .add_attribute("status", (x.project_status as u32).to_string()))
+}
+
+pub fn try_reopenmilestonevote(
+   deps: DepsMut,
+   env: Env,
+   info: MessageInfo,
+   project_id: Uint64,
+) -> Result<Response, ContractError> {
+   let mut x: ProjectState = projectstates().load(deps.storage, project_id.u64())?;
+   //-----------creator or any holder, backers aren't stuck without creator-----
+   if info.sender != x.creator_wallet
+      && held_positions(deps.storage, project_id, &info.sender)?.is_empty()
+   {
+      return Err(ContractError::Unauthorized {});
+   }
+   if x.project_status != ProjectStatus::Releasing {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   let step = x.project_milestonestep.u128() as u64;
+   let mut milestone = MILESTONES.load(deps.storage, (project_id.u64(), step))?;
+   if milestone.milestone_status != Uint128::new(3) {
+      //only rejected milestone
+      return Err(ContractError::NotCorrectMilestoneStatus {
+         step: step as usize,
+         status: milestone.milestone_status,
+      });
+   }
+
+   //-----------open a new vote round and voting period---------------
+   milestone.milestone_status = Uint128::zero();
+   MILESTONES.save(deps.storage, (project_id.u64(), step), &milestone)?;
+   open_milestone_vote(&mut x, &env);
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+
+   Ok(Response::new().add_attribute("action", "reopen milestone vote"))
+}
+
+pub fn try_setfundraisingstage(
+   deps: DepsMut,
+   info: MessageInfo,
+   project_id: Uint64,
+   stage: Uint128,
+) -> Result<Response, ContractError> {
+   check_role(deps.storage, &info.sender, Role::Operator)?;
+
+   projectstates().update(deps.storage, project_id.u64(), |op| match op {
+      None => Err(ContractError::NotRegisteredProject {}),
+      Some(mut project) => {
+         project.fundraising_stage = stage;
+         Ok(project)
+      }
+   })?;
+
+   Ok(Response::new().add_attribute("action", "Set Fundraising stage"))
+}
+
+pub fn try_proposeproject(
+   deps: DepsMut,
+   env: Env,
+   info: MessageInfo,
+   project_id: Uint64,
+   fee_bps: Option<Uint64>,
+) -> Result<Response, ContractError> {
+   let mut x: ProjectState = projectstates().load(deps.storage, project_id.u64())?;
+   //-----------creator or admin proposes, only admin sets fee-------------
+   let is_admin = check_role(deps.storage, &info.sender, Role::Admin).is_ok();
+   if (info.sender != x.creator_wallet || fee_bps.is_some()) && !is_admin {
+      return Err(ContractError::Unauthorized {});
+   }
+   if x.project_status != ProjectStatus::WefundVote {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   if !x.approval_vote_end.is_zero() {
+      return Err(ContractError::AlreadyProposed {});
+   }
+   if let Some(fee_bps) = fee_bps {
+      check_fee_bps(fee_bps)?;
+   }
+
+   let config = CONFIG.load(deps.storage)?;
+   let community = COMMUNITY.load(deps.storage)?;
+   x.fee_bps = fee_bps;
+   x.approval_total = Uint64::new(community.len() as u64);
+   x.approval_vote_end = Uint64::new(env.block.time.seconds()) + config.approval_vote_period;
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+   APPROVAL_VOTERS.save(deps.storage, project_id.u64(), &community)?;
+
+   Ok(Response::new()
+      .add_attribute("action", "propose project")
+      .add_attribute("vote_end", x.approval_vote_end))
+}
+
+pub fn try_setapprovalvote(
+   deps: DepsMut,
+   env: Env,
+   info: MessageInfo,
+   project_id: Uint64,
+   option: VoteOption,
+) -> Result<Response, ContractError> {
+   let mut x: ProjectState = projectstates().load(deps.storage, project_id.u64())?;
+   if x.project_status != ProjectStatus::WefundVote || x.approval_vote_end.is_zero() {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   if env.block.time.seconds() >= x.approval_vote_end.u64() {
+      return Err(ContractError::VotingEnded {});
+   }
+
+   //------one vote per member at proposal time, last vote counts------
+   let voters = APPROVAL_VOTERS.load(deps.storage, project_id.u64())?;
+   if !voters.contains(&info.sender) {
+      return Err(ContractError::NotRegisteredCommunity {});
+   }
+   if let Some(prev) = APPROVAL_VOTES.may_load(deps.storage, (project_id.u64(), &info.sender))? {
+      tally_approval_vote(&mut x, &prev, false);
+   }
+   tally_approval_vote(&mut x, &option, true);
+   APPROVAL_VOTES.save(deps.storage, (project_id.u64(), &info.sender), &option)?;
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+
+   Ok(Response::new()
+      .add_event(
+         Event::new(EVENT_VOTE_CAST)
+            .add_attribute(ATTR_PROJECT_ID, project_id.to_string())
+            .add_attribute(ATTR_KIND, VOTE_KIND_APPROVAL)
+            .add_attribute(ATTR_WALLET, info.sender.to_string())
+            .add_attribute(ATTR_OPTION, format!("{:?}", option))
+            .add_attribute(ATTR_WEIGHT, "1"),
+      )
+      .add_attribute("action", "Set approval vote"))
+}
+
+fn tally_approval_vote(x: &mut ProjectState, option: &VoteOption, add: bool) {
+   let counter = match option {
+      VoteOption::Yes => &mut x.approval_yes,
+      VoteOption::No => &mut x.approval_no,
+      VoteOption::Abstain => &mut x.approval_abstain,
+   };
+   match add {
+      true => *counter += Uint64::new(1),
+      false => *counter -= Uint64::new(1),
+   }
+}
+
+//---------members who joined after proposal don't count for quorum---------------
+pub fn calc_approval_tally(config: &Config, x: &ProjectState) -> ApprovalTallyResponse {
+   let yes = x.approval_yes;
+   let no = x.approval_no;
+   let abstain = x.approval_abstain;
+   let total = x.approval_total;
+
+   let voted = Uint128::from((yes + no + abstain).u64());
+   let quorum_reached = voted >= Uint128::from(total.u64()) * config.approval_quorum;
+   let passed = quorum_reached
+      && !yes.is_zero()
+      && Uint128::from(yes.u64()) >= Uint128::from((yes + no).u64()) * config.approval_threshold;
+
+   ApprovalTallyResponse {
+      yes,
+      no,
+      abstain,
+      total,
+      quorum: config.approval_quorum,
+      threshold: config.approval_threshold,
+      quorum_reached,
+      passed,
+      vote_end: x.approval_vote_end,
+   }
+}
+
+pub fn try_tallyapproval(
+   deps: DepsMut,
+   env: Env,
+   project_id: Uint64,
+) -> Result<Response, ContractError> {
+   let mut x: ProjectState = projectstates().load(deps.storage, project_id.u64())?;
+   if x.project_status != ProjectStatus::WefundVote || x.approval_vote_end.is_zero() {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   //-----------anyone can tally after voting period ended-------------
+   if env.block.time.seconds() < x.approval_vote_end.u64() {
+      return Err(ContractError::VotingNotEnded {});
+   }
+
+   let config = CONFIG.load(deps.storage)?;
+   let tally = calc_approval_tally(&config, &x);
+   //-----------passed: bond back, failed: bond slashed----------------
+   let (event, msgs) = if tally.passed {
+      let event = transition_project(&mut x, ProjectStatus::Whitelist)?;
+      (event, return_bond(deps.storage, project_id.u64())?)
+   } else {
+      let event = transition_project(&mut x, ProjectStatus::Rejected)?;
+      x.rejected_reason = match tally.quorum_reached {
+         true => "threshold not reached".to_string(),
+         false => "quorum not reached".to_string(),
+      };
+      (event, slash_bond(deps.storage, project_id.u64())?)
+   };
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+
+   Ok(Response::new()
+      .add_messages(msgs)
+      .add_event(event)
+      .add_attribute("action", "tally approval")
+      .add_attribute("passed", tally.passed.to_string()))
+}
+
+//---------owner's emergency veto before fundraising---------------------------
+pub fn try_vetoproject(
+   deps: DepsMut,
+   info: MessageInfo,
+   project_id: Uint64,
+   reason: String,
+) -> Result<Response, ContractError> {
+   let config = CONFIG.load(deps.storage)?;
+   if info.sender != config.owner {
+      return Err(ContractError::Unauthorized {});
+   }
+
+   let mut x: ProjectState = projectstates().load(deps.storage, project_id.u64())?;
+   let event = transition_project(&mut x, ProjectStatus::Rejected)?;
+   x.rejected_reason = reason;
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+   let msgs = slash_bond(deps.storage, project_id.u64())?;
+
+   Ok(Response::new()
+      .add_messages(msgs)
+      .add_event(event)
+      .add_attribute("action", "veto project"))
+}
+
+pub fn try_setapprovalconfig(
+   deps: DepsMut,
+   info: MessageInfo,
+   quorum: Decimal,
+   threshold: Decimal,
+   vote_period: Uint64,
+) -> Result<Response, ContractError> {
+   //-----------check admin--------------------------
+   check_role(deps.storage, &info.sender, Role::Admin)?;
+   if quorum > Decimal::one() || threshold > Decimal::one() || threshold.is_zero() {
+      return Err(ContractError::InvalidVotingConfig {});
+   }
+   if vote_period.is_zero() {
+      return Err(ContractError::InvalidVotingConfig {});
+   }
+
+   let mut config = CONFIG.load(deps.storage)?;
+   config.approval_quorum = quorum;
+   config.approval_threshold = threshold;
+   config.approval_vote_period = vote_period;
+   CONFIG.save(deps.storage, &config)?;
+
+   Ok(Response::new().add_attribute("action", "Set approval config"))
+}
+
+//---------bond goes back to who paid it------------------------------------------
+fn return_bond(store: &mut dyn Storage, project_id: u64) -> StdResult<Vec<CosmosMsg>> {
+   let mut msgs: Vec<CosmosMsg> = Vec::new();
+   if let Some(mut bond) = BONDS.may_load(store, project_id)? {
+      if bond.status == BondStatus::Held {
+         msgs.push(send_fund(bond.amount.clone(), bond.is_cw20, &bond.wallet)?);
+         bond.status = BondStatus::Returned;
+         BONDS.save(store, project_id, &bond)?;
+      }
+   }
+   Ok(msgs)
+}
+
+//---------slashed part of bond to wefund, rest back to who paid it-----------------
+fn slash_bond(store: &mut dyn Storage, project_id: u64) -> StdResult<Vec<CosmosMsg>> {
+   let mut msgs: Vec<CosmosMsg> = Vec::new();
+   if let Some(mut bond) = BONDS.may_load(store, project_id)? {
+      if bond.status == BondStatus::Held {
+         let config = CONFIG.load(store)?;
+         let slashed = calc_fee(bond.amount.amount, config.bond_slash_bps);
+         let returned = bond.amount.amount - slashed;
+         if !slashed.is_zero() {
+            let fund = Coin::new(slashed.u128(), bond.amount.denom.clone());
+            msgs.push(send_fund(fund, bond.is_cw20, &config.wefund)?);
+         }
+         if !returned.is_zero() {
+            let fund = Coin::new(returned.u128(), bond.amount.denom.clone());
+            msgs.push(send_fund(fund, bond.is_cw20, &bond.wallet)?);
+         }
+         bond.status = BondStatus::Slashed;
+         bond.slashed = slashed;
+         BONDS.save(store, project_id, &bond)?;
+      }
+   }
+   Ok(msgs)
+}
+
+pub fn try_removecommunitymember(
+   deps: DepsMut,
+   info: MessageInfo,
+   wallet: String,
+) -> Result<Response, ContractError> {
+   check_role(deps.storage, &info.sender, Role::CommunityManager)?;
+   let wallet = deps.api.addr_validate(&wallet).unwrap();
+
+   let mut community = COMMUNITY.load(deps.storage).unwrap();
+   let res = community.iter().find(|&x| x == &wallet);
+   if res.is_none() {
+      return Err(ContractError::NotRegisteredCommunity {});
+   }
+
+   community.retain(|x| x != &wallet);
+   COMMUNITY.save(deps.storage, &community)?;
+
+   Ok(Response::new().add_attribute("action", "remove community member"))
+}
+
+pub fn try_addcommunitymember(
+   deps: DepsMut,
+   info: MessageInfo,
+   wallet: String,
+) -> Result<Response, ContractError> {
+   check_role(deps.storage, &info.sender, Role::CommunityManager)?;
+   let wallet = deps.api.addr_validate(&wallet).unwrap();
+
+   let mut community = COMMUNITY.load(deps.storage).unwrap();
+   let res = community.iter().find(|&x| x == &wallet);
+   if res.is_some() {
+      return Err(ContractError::AlreadyRegisteredCommunity {});
+   }
+
+   community.push(wallet);
+   COMMUNITY.save(deps.storage, &community)?;
+
+   Ok(Response::new().add_attribute("action", "add community member"))
+}
+pub fn try_setaccepteddenom(
+   deps: DepsMut,
+   info: MessageInfo,
+   denom: String,
+   is_cw20: bool,
+   rate: Decimal,
+) -> Result<Response, ContractError> {
+   //-----------check admin--------------------------
+   check_role(deps.storage, &info.sender, Role::Admin)?;
+   //-----------cw20 denom is token contract address-----------
+   let denom = match is_cw20 {
+      true => deps.api.addr_validate(&denom)?.to_string(),
+      false => denom,
+   };
+
+   let accepted = AcceptedDenom {
+      denom: denom.clone(),
+      is_cw20,
+      rate,
+   };
+   ACCEPTED_DENOMS.save(deps.storage, &denom, &accepted)?;
+   if is_cw20 {
+      CW20_TOKENS.save(deps.storage, &denom, &Empty {})?;
+   }
+
+   Ok(Response::new()
+      .add_attribute("action", "set accepted denom")
+      .add_attribute("denom", denom)
+      .add_attribute("rate", rate.to_string()))
+}
+
+pub fn try_removeaccepteddenom(
+   deps: DepsMut,
+   info: MessageInfo,
+   denom: String,
+) -> Result<Response, ContractError> {
+   //-----------check admin--------------------------
+   check_role(deps.storage, &info.sender, Role::Admin)?;
+   if !ACCEPTED_DENOMS.has(deps.storage, &denom) {
+      return Err(ContractError::NotAcceptedDenom { denom });
+   }
+   ACCEPTED_DENOMS.remove(deps.storage, &denom);
+
+   Ok(Response::new()
+      .add_attribute("action", "remove accepted denom")
+      .add_attribute("denom", denom))
+}
+
+pub fn try_transferallcoins(
+   deps: DepsMut,
+   _env: Env,
+   info: MessageInfo,
+   wallet: String,
+) -> Result<Response, ContractError> {
+   //-----------check owner--------------------------
+   let config = CONFIG.load(deps.storage)?;
+   if info.sender != config.owner {
+      return Err(ContractError::Unauthorized {});
+   }
+   let wallet = deps.api.addr_validate(&wallet)?;
+   //--------get all native coins and ust - 4 ----------------------
+   let balance: AllBalanceResponse =
+      deps
+         .querier
+         .query(&QueryRequest::Bank(BankQuery::AllBalances {
+            address: _env.contract.address.to_string(),
+         }))?;
+
+   //--------escrow of every project and held bonds stay here-------
+   let mut escrow: Vec<Coin> = Vec::new();
+   let projects: StdResult<Vec<_>> = projectstates()
+      .range(deps.storage, None, None, Order::Ascending)
+      .collect();
+   for (_, x) in projects? {
+      for fund in x.backerbacked_funds.iter() {
+         add_coin(&mut escrow, fund);
+      }
+   }
+   let bonds: StdResult<Vec<_>> = BONDS
+      .range(deps.storage, None, None, Order::Ascending)
+      .collect();
+   for (_, bond) in bonds? {
+      if bond.status == BondStatus::Held && !bond.is_cw20 {
+         add_coin(&mut escrow, &bond.amount);
+      }
+   }
+
+   let mut amount: Vec<Coin> = Vec::new();
+   for mut fund in balance.amount {
+      if let Some(held) = escrow.iter().find(|x| x.denom == fund.denom) {
+         fund.amount = fund.amount.saturating_sub(held.amount);
+      }
+      if !fund.amount.is_zero() {
+         amount.push(fund);
+      }
+   }
+   if amount.is_empty() {
+      return Err(ContractError::NeedCoin {});
+   }
+
+   let bank_native = BankMsg::Send {
+      to_address: wallet.to_string(),
+      amount,
+   };
+
+   Ok(Response::new()
+      .add_message(CosmosMsg::Bank(bank_native))
+      .add_attribute("action", "trasnfer all coins"))
+}
+pub fn try_removeproject(
+   deps: DepsMut,
+   info: MessageInfo,
+   project_id: Uint64,
+) -> Result<Response, ContractError> {
+   //-----------check admin--------------------------
+   check_role(deps.storage, &info.sender, Role::Admin)?;
+   let x = projectstates()
+      .may_load(deps.storage, project_id.u64())?
+      .ok_or(ContractError::NotRegisteredProject {})?;
+   //-------only projects that never opened for backing---------------
+   if x.project_status != ProjectStatus::WefundVote && x.project_status != ProjectStatus::Rejected {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   for step in 0..x.milestone_count.u64() {
+      MILESTONES.remove(deps.storage, (project_id.u64(), step));
+   }
+   projectstates().remove(deps.storage, project_id.u64())?;
+
+   let msgs = slash_bond(deps.storage, project_id.u64())?;
+   clear_project(deps.storage, project_id.u64())?;
+
+   Ok(Response::new()
+      .add_messages(msgs)
+      .add_attribute("action", "remove project"))
+}
+
+//---------state kept per project before fundraising, slashed bond stays-------
+fn clear_project(store: &mut dyn Storage, project_id: u64) -> StdResult<()> {
+   let wallets: StdResult<Vec<Addr>> = WHITELIST
+      .prefix(project_id)
+      .keys(store, None, None, Order::Ascending)
+      .collect();
+   for wallet in wallets? {
+      WHITELIST.remove(store, (project_id, &wallet));
+   }
+   let wallets: StdResult<Vec<Addr>> = MERKLE_WHITELIST
+      .prefix(project_id)
+      .keys(store, None, None, Order::Ascending)
+      .collect();
+   for wallet in wallets? {
+      MERKLE_WHITELIST.remove(store, (project_id, &wallet));
+   }
+   let wallets: StdResult<Vec<Addr>> = APPROVAL_VOTES
+      .prefix(project_id)
+      .keys(store, None, None, Order::Ascending)
+      .collect();
+   for wallet in wallets? {
+      APPROVAL_VOTES.remove(store, (project_id, &wallet));
+   }
+   APPROVAL_VOTERS.remove(store, project_id);
+   MERKLE_ROOTS.remove(store, project_id);
+   LOTTERIES.remove(store, project_id);
+   WHITELIST_COMMUNITY.remove(store, project_id);
+   WHITELIST_RECHECKED.remove(store, project_id);
+   Ok(())
+}
+
+//---------owner holds every role, admin role passes every check----------------
+pub fn check_role(store: &dyn Storage, wallet: &Addr, role: Role) -> Result<(), ContractError> {
+   let config = CONFIG.load(store)?;
+   if wallet == &config.owner {
+      return Ok(());
+   }
+   let roles = ROLES.may_load(store, wallet)?.unwrap_or_default();
+   if roles.contains(&Role::Admin) || roles.contains(&role) {
+      return Ok(());
+   }
+   Err(ContractError::MissingRole { role })
+}
+
+pub fn try_grantrole(
+   deps: DepsMut,
+   info: MessageInfo,
+   wallet: String,
+   role: Role,
+) -> Result<Response, ContractError> {
+   check_role(deps.storage, &info.sender, Role::Admin)?;
+   let wallet = deps.api.addr_validate(&wallet)?;
+
+   let mut roles = ROLES.may_load(deps.storage, &wallet)?.unwrap_or_default();
+   if !roles.contains(&role) {
+      roles.push(role.clone());
+      ROLES.save(deps.storage, &wallet, &roles)?;
+   }
+
+   Ok(Response::new()
+      .add_attribute("action", "grant role")
+      .add_attribute("wallet", wallet)
+      .add_attribute("role", format!("{:?}", role)))
+}
+
+pub fn try_revokerole(
+   deps: DepsMut,
+   info: MessageInfo,
+   wallet: String,
+   role: Role,
+) -> Result<Response, ContractError> {
+   check_role(deps.storage, &info.sender, Role::Admin)?;
+   let wallet = deps.api.addr_validate(&wallet)?;
+
+   let mut roles = ROLES.may_load(deps.storage, &wallet)?.unwrap_or_default();
+   if !roles.contains(&role) {
+      return Err(ContractError::MissingRole { role });
+   }
+   roles.retain(|x| x != &role);
+   if roles.is_empty() {
+      ROLES.remove(deps.storage, &wallet);
+   } else {
+      ROLES.save(deps.storage, &wallet, &roles)?;
+   }
+
+   Ok(Response::new()
+      .add_attribute("action", "revoke role")
+      .add_attribute("wallet", wallet)
+      .add_attribute("role", format!("{:?}", role)))
+}
+
+//---------fields of SetConfig, unset ones keep their value---------------------
+pub struct ConfigParams {
+   pub admin: Option<String>,
+   pub wefund: Option<String>,
+   pub denom: Option<String>,
+   pub decimals: Option<Uint64>,
+   pub vesting_contract: Option<String>,
+   pub staking_contract: Option<String>,
+   pub fee_bps: Option<Uint64>,
+   pub fee_recipients: Option<Vec<FeeRecipient>>,
+   pub bond_amount: Option<Uint128>,
+   pub bond_token: Option<String>,
+   pub bond_token_amount: Option<Uint128>,
+   pub bond_slash_bps: Option<Uint64>,
+}
+
+pub fn try_setconfig(
+   deps: DepsMut,
+   _env: Env,
+   info: MessageInfo,
+   params: ConfigParams,
+) -> Result<Response, ContractError> {
+   let ConfigParams {
+      admin,
+      wefund,
+      denom,
+      decimals,
+      vesting_contract,
+      staking_contract,
+      fee_bps,
+      fee_recipients,
+      bond_amount,
+      bond_token,
+      bond_token_amount,
+      bond_slash_bps,
+   } = params;
+   //-----------check admin--------------------------
+   check_role(deps.storage, &info.sender, Role::Admin)?;
+   let mut config = CONFIG.load(deps.storage).unwrap();
+   //-----------only owner hands over ownership---------------
+   if admin.is_some() && info.sender != config.owner {
+      return Err(ContractError::Unauthorized {});
+   }
+
+   config.owner = admin
+      .and_then(|s| deps.api.addr_validate(s.as_str()).ok())
+      .unwrap_or(config.owner);
+
+   config.wefund = wefund
+      .and_then(|s| deps.api.addr_validate(s.as_str()).ok())
+      .unwrap_or(config.wefund);
+
+   config.denom = denom.unwrap_or(config.denom);
+
+   config.decimals = decimals.unwrap_or(Uint64::from(config.decimals)).u64() as u32;
+
+   config.vesting_contract = vesting_contract
+      .and_then(|s| deps.api.addr_validate(s.as_str()).ok())
+      .unwrap_or(config.vesting_contract);
+
+   config.staking_contract = staking_contract
+      .and_then(|s| deps.api.addr_validate(s.as_str()).ok())
+      .unwrap_or(config.staking_contract);
+
+   if let Some(fee_bps) = fee_bps {
+      config.fee_bps = check_fee_bps(fee_bps)?;
+   }
+   if let Some(fee_recipients) = fee_recipients {
+      config.fee_recipients = check_fee_recipients(deps.as_ref(), fee_recipients)?;
+   }
+
+   config.bond_amount = bond_amount.unwrap_or(config.bond_amount);
+   config.bond_token = bond_token
+      .and_then(|s| deps.api.addr_validate(s.as_str()).ok())
+      .unwrap_or(config.bond_token);
+   config.bond_token_amount = bond_token_amount.unwrap_or(config.bond_token_amount);
+   if let Some(bond_slash_bps) = bond_slash_bps {
+      config.bond_slash_bps = check_fee_bps(bond_slash_bps)?;
+   }
+
+   CONFIG.save(deps.storage, &config)?;
+
+   Ok(Response::new().add_attribute("action", "SetConfig"))
+}
+pub fn try_completeproject(
+   deps: DepsMut,
+   _env: Env,
+   info: MessageInfo,
+   _project_id: Uint64,
+) -> Result<Response, ContractError> {
+   //-----------releases whole escrow without milestone votes--------------
+   check_role(deps.storage, &info.sender, Role::Admin)?;
+
+   //--------Get project info----------------------------
+   let mut x: ProjectState = projectstates().load(deps.storage, _project_id.u64())?;
+
+   //--------Checking project status-------------------------
+   if x.project_status != ProjectStatus::Releasing {
+      //only releasing status
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+
+   let release_amount = x.backerbacked_amount;
+
+   let mut msgs: Vec<CosmosMsg> = Vec::new();
+   for fund in take_escrow(&mut x, release_amount) {
+      msgs.push(transfer_fund(deps.storage, fund, &x.creator_wallet)?);
+   }
+
+   let event = transition_project(&mut x, ProjectStatus::Done)?;
+   projectstates().save(deps.storage, _project_id.u64(), &x)?;
+
+   Ok(Response::new()
+      .add_messages(msgs)
+      .add_event(event)
+      .add_attribute("action", "complete milestone")
+      .add_attribute("withdraw aust amount", release_amount.to_string()))
+}
+pub fn try_failproject(
+   deps: DepsMut,
+   _env: Env,
+   info: MessageInfo,
+   _project_id: Uint64,
+) -> Result<Response, ContractError> {
+   //-----------check admin--------------------------
+   check_role(deps.storage, &info.sender, Role::Admin)?;
+   //--------Get project info----------------------------
+   let mut x: ProjectState = projectstates().load(deps.storage, _project_id.u64())?;
+
+   //--------Checking project status-------------------------
+   if !is_fundraising(&x) && x.project_status != ProjectStatus::Releasing {
+      //only fundraising or releasing status
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+
+   //---------freeze milestones, the remaining escrow is refunded to backers------
+   let event = transition_project(&mut x, ProjectStatus::Fail)?;
+   projectstates().save(deps.storage, _project_id.u64(), &x)?;
+
+   Ok(Response::new()
+      .add_event(event)
+      .add_attribute("action", "failed project")
+      .add_attribute("refundable amount", x.backerbacked_amount.to_string()))
+}
+
+//---------backer's pro-rata share of the escrow left on a failed project------------
+pub fn calc_refundable(
+   store: &dyn Storage,
+   x: &ProjectState,
+   wallet: &Addr,
+) -> StdResult<Vec<Coin>> {
+   let mut refundable: Vec<Coin> = Vec::new();
+   for position in held_positions(store, x.project_id, wallet)? {
+      if let Some(fund) = calc_position_refund(x, &position) {
+         add_coin(&mut refundable, &fund);
+      }
+   }
+   Ok(refundable)
+}
+
+//---------position's share of the escrow left in its denom------------------------
+fn calc_position_refund(x: &ProjectState, position: &Position) -> Option<Coin> {
+   if position.refunded || position.crosschain {
+      return None;
+   }
+   let denom = &position.amount.denom;
+   let escrow = x.backerbacked_funds.iter().find(|x| &x.denom == denom)?;
+   let total_backed = x
+      .backerbacked_total_funds
+      .iter()
+      .find(|x| &x.denom == denom)
+      .map(|x| x.amount)
+      .unwrap_or_default();
+   if total_backed.is_zero() {
+      return None;
+   }
+
+   let amount = position
+      .amount
+      .amount
+      .multiply_ratio(escrow.amount, total_backed);
+   if amount.is_zero() {
+      return None;
+   }
+   Some(Coin {
+      denom: denom.clone(),
+      amount,
+   })
+}
+
+pub fn try_claimrefund(
+   deps: DepsMut,
+   _env: Env,
+   info: MessageInfo,
+   project_id: Uint64,
+) -> Result<Response, ContractError> {
+   let x: ProjectState = projectstates().load(deps.storage, project_id.u64())?;
+   //-------check project status-------------------
+   if x.project_status != ProjectStatus::Fail {
+      //only fail status
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+
+   //-------refund goes to whoever holds the positions now------
+   let mut refundable: Vec<Coin> = Vec::new();
+   for mut position in held_positions(deps.storage, project_id, &info.sender)? {
+      if let Some(fund) = calc_position_refund(&x, &position) {
+         add_coin(&mut refundable, &fund);
+         position.refunded = true;
+         positions().save(deps.storage, position.token_id.u64(), &position)?;
+      }
+   }
+   if refundable.is_empty() {
+      return Err(ContractError::NothingToRefund {});
+   }
+
+   let mut msgs: Vec<CosmosMsg> = Vec::new();
+   for fund in refundable.iter() {
+      msgs.push(transfer_fund(deps.storage, fund.clone(), &info.sender)?);
+   }
+
+   Ok(Response::new()
+      .add_messages(msgs)
+      .add_event(
+         Event::new(EVENT_REFUND_CLAIMED)
+            .add_attribute(ATTR_PROJECT_ID, project_id.to_string())
+            .add_attribute(ATTR_WALLET, info.sender.to_string())
+            .add_attribute(ATTR_AMOUNT, coins_to_string(&refundable)),
+      )
+      .add_attribute("action", "claim refund")
+      .add_attribute("amount", coins_to_string(&refundable)))
+}
+
+pub fn try_submitproject(
+   deps: DepsMut,
+   _env: Env,
+   info: MessageInfo,
+   project_info: ProjectInfo,
+) -> Result<Response, ContractError> {
+   let config = CONFIG.load(deps.storage)?;
+   let bond = check_bond(&config, &info.funds, false)?;
+   submit_project(deps, _env, info.sender, project_info, bond, false)
+}
+
+fn submit_project(
+   deps: DepsMut,
+   _env: Env,
+   sender: Addr,
+   project_info: ProjectInfo,
+   bond: Option<Coin>,
+   is_cw20: bool,
+) -> Result<Response, ContractError> {
+   let mut new_project: ProjectState = ProjectState {
+      //-----------set from project info-------------------
+      project_company: String::new(),
+      project_title: String::new(),
+      project_description: String::new(),
+      project_ecosystem: String::new(),
+      project_fundtype: String::new(),
+      project_createddate: String::new(),
+      project_saft: String::new(),
+      project_logo: String::new(),
+      project_whitepaper: String::new(),
+      project_website: String::new(),
+      project_email: String::new(),
+      country: String::new(),
+      cofounder_name: String::new(),
+      service_wefund: String::new(),
+      service_charity: String::new(),
+      professional_link: String::new(),
+      creator_wallet: Addr::unchecked(""),
+      project_collected: Uint128::zero(),
+      project_softcap: Uint128::zero(),
+      teammember_states: Vec::new(),
+      vesting: Vec::new(),
+      token_addr: Addr::unchecked(""),
+      //-----------------------------------
+      project_id: Uint64::zero(), //auto increment
+      revision: Uint64::zero(),
+      previous_hash: String::new(),
+      project_status: ProjectStatus::WefundVote,
+      fundraising_stage: Uint128::zero(),
+      fee_bps: None,
+
+      approval_vote_end: Uint64::zero(),
+      approval_total: Uint64::zero(),
+      approval_yes: Uint64::zero(),
+      approval_no: Uint64::zero(),
+      approval_abstain: Uint64::zero(),
+      rejected_reason: String::new(),
+
+      backerbacked_amount: Uint128::zero(),
+      backerbacked_funds: Vec::new(),
+      backerbacked_total_funds: Vec::new(),
+      crosschain_backed_amount: Uint128::zero(),
+      backer_count: Uint64::zero(),
+
+      milestone_count: Uint64::zero(),
+      project_milestonestep: Uint128::zero(), //first milestonestep
+      milestone_quorum: Decimal::percent(50),
+      milestone_threshold: Decimal::percent(50),
+      milestone_vote_period: Uint64::new(7 * 24 * 60 * 60), //a week
+      milestone_vote_end: Uint64::zero(),
+      milestone_max_rejections: Uint64::new(3),
+      milestone_rejections: Uint64::zero(),
+      milestone_vote_round: Uint64::zero(),
+      milestone_total_weight: Uint128::zero(),
+      milestone_yes: Uint128::zero(),
+      milestone_no: Uint128::zero(),
+      milestone_abstain: Uint128::zero(),
+
+      whitelist_weight: Uint128::zero(),
+      tier_weights: default_tier_weights(),
+      holder_alloc: Uint128::from(80u128),
+      holder_ticket: Uint128::zero(),
+      community_ticket: Uint128::zero(),
+      whitelist_period: None,
+      fundraising_period: None,
+      public_sale: None,
+      public_start: Uint64::zero(),
+   };
+   let milestones = apply_project_info(deps.as_ref(), &mut new_project, project_info)?;
+   //-----------creator submits for itself-------------------
+   if new_project.creator_wallet != sender {
+      return Err(ContractError::Unauthorized {});
+   }
+   save_projectstate(deps.storage, &mut new_project)?;
+   save_milestones(deps.storage, &new_project, 0, &milestones)?;
+
+   if let Some(amount) = bond {
+      let bond = BondState {
+         wallet: sender,
+         amount,
+         is_cw20,
+         status: BondStatus::Held,
+         slashed: Uint128::zero(),
+      };
+      BONDS.save(deps.storage, new_project.project_id.u64(), &bond)?;
+   }
+
+   Ok(Response::new()
+      .add_messages(add_vesting_project(deps.as_ref(), &_env, &new_project)?)
+      .add_event(
+         Event::new(EVENT_PROJECT_CREATED)
+            .add_attribute(ATTR_PROJECT_ID, new_project.project_id.to_string())
+            .add_attribute(ATTR_CREATOR, new_project.creator_wallet.to_string()),
+      )
+      .add_attribute("action", "submit project")
+      .add_attribute("id", new_project.project_id))
+}
+
+pub fn try_updateproject(
+   deps: DepsMut,
+   _env: Env,
+   info: MessageInfo,
+   project_id: Uint64,
+   project_info: ProjectInfo,
+) -> Result<Response, ContractError> {
+   let mut x: ProjectState = projectstates().load(deps.storage, project_id.u64())?;
+   //-----------creator or admin, and only before wefund approve-------------
+   let is_admin = check_role(deps.storage, &info.sender, Role::Admin).is_ok();
+   if info.sender != x.creator_wallet && !is_admin {
       return Err(ContractError::Unauthorized {});
    }
-   let mut config = CONFIG.load(deps.storage).unwrap();
+   if x.project_status != ProjectStatus::WefundVote {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   //-----------community votes on what was proposed-------------------
+   if !x.approval_vote_end.is_zero() {
+      return Err(ContractError::AlreadyProposed {});
+   }
 
-   config.owner = admin
-      .and_then(|s| deps.api.addr_validate(s.as_str()).ok())
-      .unwrap_or(config.owner);
+   let old_milestones = load_milestones(deps.storage, &x)?;
+   let old_milestone_count = x.milestone_count.u64();
+   let previous_hash = revision_hash(&x, &old_milestones)?;
+   let old_creator = x.creator_wallet.clone();
 
-   config.wefund = wefund
-      .and_then(|s| deps.api.addr_validate(s.as_str()).ok())
-      .unwrap_or(config.wefund);
+   let milestones = apply_project_info(deps.as_ref(), &mut x, project_info)?;
+   //-----------only admin hands project over to another creator----------
+   if x.creator_wallet != old_creator && !is_admin {
+      return Err(ContractError::Unauthorized {});
+   }
 
-   config.denom = denom.unwrap_or(config.denom);
+   x.revision += Uint64::new(1);
+   x.previous_hash = previous_hash;
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+   save_milestones(deps.storage, &x, old_milestone_count, &milestones)?;
 
-   config.decimals = decimals.unwrap_or(Uint64::from(config.decimals)).u64() as u32;
+   Ok(Response::new()
+      .add_messages(add_vesting_project(deps.as_ref(), &_env, &x)?)
+      .add_event(
+         Event::new(EVENT_PROJECT_UPDATED)
+            .add_attribute(ATTR_PROJECT_ID, x.project_id.to_string())
+            .add_attribute(ATTR_CREATOR, x.creator_wallet.to_string())
+            .add_attribute(ATTR_REVISION, x.revision.to_string()),
+      )
+      .add_attribute("action", "update project")
+      .add_attribute("id", x.project_id)
+      .add_attribute("revision", x.revision))
+}
 
-   config.vesting_contract = vesting_contract
-      .and_then(|s| deps.api.addr_validate(s.as_str()).ok())
-      .unwrap_or(config.vesting_contract);
+//---------bond paid on submit, in denom or bond token---------------------
+fn check_bond(
+   config: &Config,
+   funds: &[Coin],
+   is_cw20: bool,
+) -> Result<Option<Coin>, ContractError> {
+   let native_bond = !config.bond_amount.is_zero();
+   let token_bond = config.bond_token != "" && !config.bond_token_amount.is_zero();
+   if !native_bond && !token_bond {
+      //-----no bond required, stray funds would be kept without record-----
+      if !funds.is_empty() {
+         return Err(ContractError::InvalidBond {});
+      }
+      return Ok(None);
+   }
 
-   CONFIG.save(deps.storage, &config)?;
+   let bond = if is_cw20 {
+      Coin::new(
+         config.bond_token_amount.u128(),
+         config.bond_token.to_string(),
+      )
+   } else {
+      Coin::new(config.bond_amount.u128(), config.denom.clone())
+   };
+   if bond.amount.is_zero() || funds != [bond.clone()] {
+      return Err(ContractError::InvalidBond {});
+   }
+   Ok(Some(bond))
+}
 
-   Ok(Response::new().add_attribute("action", "SetConfig"))
+//---------set creator editable fields, returns milestones to store apart---------
+fn apply_project_info(
+   deps: Deps,
+   x: &mut ProjectState,
+   project_info: ProjectInfo,
+) -> Result<Vec<Milestone>, ContractError> {
+   //-----------soft target defaults to the full target------------------
+   let project_softcap = project_info
+      .project_softcap
+      .unwrap_or(project_info.project_collected);
+   if project_softcap > project_info.project_collected {
+      return Err(ContractError::InvalidSoftcap {});
+   }
+
+   x.project_company = project_info.project_company;
+   x.project_title = project_info.project_title;
+   x.project_description = project_info.project_description;
+   x.project_ecosystem = project_info.project_ecosystem;
+   x.project_fundtype = project_info.project_fundtype;
+   x.project_createddate = project_info.project_createddate;
+   x.project_saft = project_info.project_saft;
+   x.project_logo = project_info.project_logo;
+   x.project_whitepaper = project_info.project_whitepaper;
+   x.project_website = project_info.project_website;
+   x.project_email = project_info.project_email;
+   x.country = project_info.country;
+   x.cofounder_name = project_info.cofounder_name;
+   x.service_wefund = project_info.service_wefund;
+   x.service_charity = project_info.service_charity;
+   x.professional_link = project_info.professional_link;
+
+   x.creator_wallet = deps.api.addr_validate(&project_info.creator_wallet)?;
+   x.project_collected = project_info.project_collected;
+   x.project_softcap = project_softcap;
+   x.teammember_states = project_info.project_teammembers;
+   x.vesting = project_info.vesting;
+   x.token_addr = deps
+      .api
+      .addr_validate(project_info.token_addr.as_str())
+      .unwrap_or(Addr::unchecked("".to_string()));
+   x.milestone_count = Uint64::new(project_info.project_milestones.len() as u64);
+   Ok(project_info.project_milestones)
 }
-pub fn try_completeproject(
+
+//-----------milestones are stored by step---------------------------
+fn save_milestones(
+   store: &mut dyn Storage,
+   x: &ProjectState,
+   old_milestone_count: u64,
+   milestones: &[Milestone],
+) -> StdResult<()> {
+   let project_id = x.project_id.u64();
+   for step in milestones.len() as u64..old_milestone_count {
+      MILESTONES.remove(store, (project_id, step));
+   }
+   for (step, milestone) in milestones.iter().enumerate() {
+      MILESTONES.save(store, (project_id, step as u64), milestone)?;
+   }
+   Ok(())
+}
+
+pub fn load_milestones(store: &dyn Storage, x: &ProjectState) -> StdResult<Vec<Milestone>> {
+   (0..x.milestone_count.u64())
+      .map(|step| MILESTONES.load(store, (x.project_id.u64(), step)))
+      .collect()
+}
+
+//---------sha256 of project and its milestones, chains revisions-------------
+pub fn revision_hash(x: &ProjectState, milestones: &[Milestone]) -> StdResult<String> {
+   let mut hasher = Sha256::new();
+   hasher.update(to_vec(x)?);
+   hasher.update(to_vec(milestones)?);
+   Ok(hex::encode(hasher.finalize()))
+}
+
+fn add_vesting_project(deps: Deps, env: &Env, x: &ProjectState) -> StdResult<Vec<CosmosMsg>> {
+   let config = CONFIG.load(deps.storage)?;
+   if config.vesting_contract == "".to_string() {
+      return Ok(vec![]);
+   }
+
+   let mut vesting_params: Vec<VestingParam> = Vec::new();
+   for param in x.vesting.iter() {
+      vesting_params.push(VestingParam {
+         soon: param.stage_soon,
+         after: param.stage_after,
+         period: param.stage_period,
+      })
+   }
+   //----------add fundraising project------------------------
+   let add_vesting_project = WasmMsg::Execute {
+      contract_addr: config.vesting_contract.to_string(),
+      msg: to_binary(&VestingMsg::AddProject {
+         project_id: x.project_id,
+         admin: env.contract.address.to_string(),
+         token_addr: x.token_addr.to_string(),
+         vesting_params: vesting_params,
+         start_time: Uint128::zero(),
+      })?,
+      funds: vec![],
+   };
+   Ok(vec![CosmosMsg::Wasm(add_vesting_project)])
+}
+
+pub fn try_back2projectwithout(
    deps: DepsMut,
-   _env: Env,
-   _project_id: Uint64,
+   env: Env,
+   info: MessageInfo,
+   attestation: Attestation,
+   pubkey: Binary,
+   signature: Binary,
 ) -> Result<Response, ContractError> {
-   //--------Get project info----------------------------
-   let mut x: ProjectState = PROJECTSTATES.load(deps.storage, _project_id.u64())?;
+   check_role(deps.storage, &info.sender, Role::Relayer)?;
+   //-------signature is bound to this chain and contract-----------
+   if attestation.chain_id != env.block.chain_id
+      || attestation.contract != env.contract.address.as_str()
+   {
+      return Err(ContractError::AttestationTarget {});
+   }
+   //-------attestation signed by a registered relayer key----------
+   let keys = RELAYER_KEYS.may_load(deps.storage)?.unwrap_or_default();
+   if !keys.contains(&pubkey) {
+      return Err(ContractError::InvalidRelayerKey {});
+   }
+   let hash = Sha256::digest(&to_vec(&attestation)?);
+   let verified = deps
+      .api
+      .secp256k1_verify(&hash, &signature, &pubkey)
+      .unwrap_or(false);
+   if !verified {
+      return Err(ContractError::InvalidSignature {});
+   }
+   //-------each source tx backs only once--------------------------
+   let source = (
+      attestation.otherchain.as_str(),
+      attestation.source_tx_hash.as_str(),
+   );
+   if ATTESTATIONS.has(deps.storage, source) {
+      return Err(ContractError::AttestationUsed {});
+   }
 
-   //--------Checking project status-------------------------
-   if x.project_status != ProjectStatus::Releasing {
-      //only releasing status
+   let project_id = attestation.project_id;
+   //-------check project exist-----------------------------------
+   let res = projectstates().may_load(deps.storage, project_id.u64());
+   if res == Ok(None) {
+      //not exist
+      return Err(ContractError::NotRegisteredProject {});
+   }
+   //--------Get project info------------------------------------
+   let mut x = projectstates().load(deps.storage, project_id.u64())?;
+   if !is_fundraising(&x) {
+      //only fundraising status
       return Err(ContractError::NotCorrectStatus {
          status: x.project_status as u32,
       });
    }
+   check_period(&env, &x.fundraising_period)?;
+   let config = CONFIG.load(deps.storage)?;
+   let accepted = load_accepted_denom(deps.storage, &config, &attestation.denom, false)?;
+   let fund = Coin {
+      denom: attestation.denom.clone(),
+      amount: attestation.amount,
+   };
+   let mut fund_real_back = fund.clone();
+   let mut fund_wefund = fund.clone();
 
-   let config = CONFIG.load(deps.storage).unwrap();
-   let release_amount: u128 = x.backerbacked_amount.u128();
-
-   let coin = Coin::new(release_amount, config.denom);
-   let send2_creator = BankMsg::Send {
-      to_address: x.creator_wallet.to_string(),
-      amount: vec![coin],
+   //--------calc amount to desposit and to wefund
+   let fee_bps = x.fee_bps.map(|x| x.u64()).unwrap_or(config.fee_bps);
+   fund_wefund.amount = calc_fee(fund.amount, fee_bps);
+   fund_real_back.amount = fund.amount - fund_wefund.amount;
+
+   let backer_wallet = deps.api.addr_validate(&attestation.backer_wallet)?;
+
+   //-----backed amount in config denom unit-------------
+   //-----surplus can't be sent back to the other chain, refuse it-------
+   let backed_amount = fund_real_back.amount * accepted.rate;
+   let collected = collected_target(&config, &x);
+   let remaining = collected.saturating_sub(backed_total(&x));
+   if backed_amount > remaining {
+      return Err(ContractError::TargetExceeded {
+         remaining,
+         amount: backed_amount,
+      });
+   }
+   let new_baker: BackerState = BackerState {
+      backer_wallet: backer_wallet.clone(),
+      otherchain: attestation.otherchain.clone(),
+      otherchain_wallet: attestation.otherchain_wallet.clone(),
+      amount: fund_real_back.clone(),
    };
+   //-----funds stay on the other chain, they count to target and votes only---
+   let position = new_position(
+      &x,
+      &new_baker,
+      backed_amount,
+      attestation.fundraising_stage,
+      attestation.token_amount,
+      true,
+   );
+   let position = add_backer(deps.storage, &mut x, new_baker, position, None)?;
 
-   x.backerbacked_amount = Uint128::zero();
-   x.project_status = ProjectStatus::Done;
-   PROJECTSTATES.save(deps.storage, _project_id.u64(), &x)?;
+   let (mut msgs, events) = release_if_collected(deps.as_ref(), &env, &mut x, collected)?;
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+   ATTESTATIONS.save(deps.storage, source, &position.token_id)?;
+   msgs.extend(vesting_adduser_msgs(&config, &position)?);
 
    Ok(Response::new()
-      .add_message(send2_creator)
-      .add_attribute("action", "complete milestone")
-      .add_attribute("withdraw aust amount", release_amount.to_string()))
+      .add_messages(msgs)
+      .add_event(backing_event(&position))
+      .add_events(events)
+      .add_attribute("action", "back to project without")
+      .add_attribute("source_tx_hash", attestation.source_tx_hash)
+      .add_attribute("fee", fund_wefund.to_string()) //settled on the other chain
+      .add_attribute("token_id", position.token_id))
 }
-pub fn try_failproject(
+
+pub fn try_addrelayerkey(
    deps: DepsMut,
-   _env: Env,
-   _project_id: Uint64,
+   info: MessageInfo,
+   pubkey: Binary,
 ) -> Result<Response, ContractError> {
-   //--------Get project info----------------------------
-   let x: ProjectState = PROJECTSTATES.load(deps.storage, _project_id.u64())?;
+   //-----------check admin--------------------------
+   check_role(deps.storage, &info.sender, Role::Admin)?;
+   //-----------compressed or uncompressed secp256k1 key----------
+   if pubkey.len() != 33 && pubkey.len() != 65 {
+      return Err(ContractError::InvalidRelayerKey {});
+   }
 
-   //--------Checking project status-------------------------
-   if x.project_status != ProjectStatus::Releasing {
-      //only releasing status
+   let mut keys = RELAYER_KEYS.may_load(deps.storage)?.unwrap_or_default();
+   if !keys.contains(&pubkey) {
+      keys.push(pubkey);
+      RELAYER_KEYS.save(deps.storage, &keys)?;
+   }
+
+   Ok(Response::new().add_attribute("action", "add relayer key"))
+}
+
+pub fn try_removerelayerkey(
+   deps: DepsMut,
+   info: MessageInfo,
+   pubkey: Binary,
+) -> Result<Response, ContractError> {
+   //-----------check admin--------------------------
+   check_role(deps.storage, &info.sender, Role::Admin)?;
+
+   let mut keys = RELAYER_KEYS.may_load(deps.storage)?.unwrap_or_default();
+   if !keys.contains(&pubkey) {
+      return Err(ContractError::InvalidRelayerKey {});
+   }
+   keys.retain(|x| x != &pubkey);
+   RELAYER_KEYS.save(deps.storage, &keys)?;
+
+   Ok(Response::new().add_attribute("action", "remove relayer key"))
+}
+
+//---------creator or admin posts root of (wallet, tier, allocation) leaves------
+pub fn try_setmerkleroot(
+   deps: DepsMut,
+   info: MessageInfo,
+   project_id: Uint64,
+   root: Binary,
+) -> Result<Response, ContractError> {
+   let x = projectstates().load(deps.storage, project_id.u64())?;
+   if info.sender != x.creator_wallet {
+      check_role(deps.storage, &info.sender, Role::Admin)?;
+   }
+   if x.project_status != ProjectStatus::Whitelist && !is_fundraising(&x) {
       return Err(ContractError::NotCorrectStatus {
          status: x.project_status as u32,
       });
    }
+   if root.len() != 32 {
+      return Err(ContractError::InvalidMerkleRoot {});
+   }
+   MERKLE_ROOTS.save(deps.storage, project_id.u64(), &root)?;
 
-   // let config = CONFIG.load(deps.storage).unwrap();
-   // let mut release_amount: u128 = x.backerbacked_amount.u128();
-   // //---------send to backer wallet-------------
-   // let mut msg = Vec::new();
-   // for backer in x.backer_states.clone() {
-   //    let mut backed = backer.amount.clone();
+   Ok(Response::new()
+      .add_attribute("action", "set merkle root")
+      .add_attribute("root", root.to_base64()))
+}
 
-   //    //---while mistone releasing, suddenly failed, distribute with %
-   //    backed.amount =
-   //       backer.amount.amount * withdraw_amount / x.backerbacked_amount.clone();
+//---------fields of Back2Project, native or through cw20 Receive---------------
+pub struct BackParams {
+   pub project_id: Uint64,
+   pub backer_wallet: String,
+   pub fundraising_stage: Uint128,
+   pub token_amount: Uint128,
+   pub otherchain: String,
+   pub otherchain_wallet: String,
+   pub whitelist_proof: Option<WhitelistProof>,
+}
 
-   //    let send2_backer = BankMsg::Send {
-   //       to_address: backer.backer_wallet.to_string(),
-   //       amount: vec![backed],
-   //    };
-   //    msg.push(CosmosMsg::Bank(send2_backer));
-   // }
+pub fn try_back2project(
+   deps: DepsMut,
+   env: Env,
+   info: MessageInfo,
+   params: BackParams,
+) -> Result<Response, ContractError> {
+   //--------check sufficient back--------------------
+   if info.funds.is_empty() {
+      return Err(ContractError::NeedCoin {});
+   }
+   //--------reject unexpected denoms-----------------
+   let config = CONFIG.load(deps.storage)?;
+   for coin in info.funds.iter() {
+      load_accepted_denom(deps.storage, &config, &coin.denom, false)?;
+   }
+   let fund = info.funds[0].clone();
+   let accepted = load_accepted_denom(deps.storage, &config, &fund.denom, false)?;
+   let extra_funds = info.funds[1..].to_vec();
 
-   Ok(Response::new()
-      // .add_submessage(withdraw)
-      .add_attribute("action", "failed milestone"))
+   let res = back2project(deps, env, info.sender.clone(), fund, accepted, params)?;
+
+   //--------refund extra coins------------------------
+   if extra_funds.is_empty() {
+      return Ok(res);
+   }
+   let refund_extra = BankMsg::Send {
+      to_address: info.sender.to_string(),
+      amount: extra_funds,
+   };
+   Ok(res.add_message(refund_extra))
 }
 
-pub fn try_addproject(
+pub fn try_receive(
    deps: DepsMut,
-   _env: Env,
-   _info: MessageInfo,
-   _project_id: Uint64,
-   _project_company: String,
-   _project_title: String,
-   _project_description: String,
-   _project_ecosystem: String,
-   _project_fundtype: String,
-   _project_createddate: String,
-   _project_saft: String,
-   _project_logo: String,
-   _project_whitepaper: String,
-   _project_website: String,
-   _project_email: String,
-   _creator_wallet: String,
-   _project_collected: Uint128,
-   _project_milestones: Vec<Milestone>,
-   _project_teammembers: Vec<TeamMember>,
-   _vesting: Vec<VestingParameter>,
-   _token_addr: String,
-
-   _country: String,
-   _cofounder_name: String,
-   _service_wefund: String,
-   _service_charity: String,
-   _professional_link: String,
+   env: Env,
+   info: MessageInfo,
+   cw20_msg: Cw20ReceiveMsg,
 ) -> Result<Response, ContractError> {
-   let token_addr = deps
-      .api
-      .addr_validate(_token_addr.as_str())
-      .unwrap_or(Addr::unchecked("".to_string()));
+   let config = CONFIG.load(deps.storage)?;
+   if cw20_msg.amount.is_zero() {
+      return Err(ContractError::NeedCoin {});
+   }
 
-   let mut new_project: ProjectState = ProjectState {
-      project_company: _project_company,
-      project_title: _project_title,
-      project_description: _project_description,
-      project_ecosystem: _project_ecosystem,
-      project_fundtype: _project_fundtype,
-      project_createddate: _project_createddate,
-      project_saft: _project_saft,
-      project_logo: _project_logo,
-      project_whitepaper: _project_whitepaper,
-      project_website: _project_website,
-      project_email: _project_email,
-      //-----------------------------------
-      project_id: Uint64::zero(), //auto increment
-      project_status: ProjectStatus::WefundVote,
-      fundraising_stage: Uint128::zero(),
+   //---------cw20 funds are tracked as coin with token address as denom------
+   let fund = Coin {
+      denom: info.sender.to_string(),
+      amount: cw20_msg.amount,
+   };
+   let sender = deps.api.addr_validate(&cw20_msg.sender)?;
 
-      backerbacked_amount: Uint128::zero(),
+   match from_binary(&cw20_msg.msg)? {
+      ReceiveMsg::Back2Project {
+         project_id,
+         backer_wallet,
+         fundraising_stage,
+         token_amount,
+         otherchain,
+         otherchain_wallet,
+         whitelist_proof,
+      } => {
+         //-----------only accepted cw20 tokens--------------------------
+         let accepted = load_accepted_denom(deps.storage, &config, info.sender.as_str(), true)?;
+         let params = BackParams {
+            project_id,
+            backer_wallet,
+            fundraising_stage,
+            token_amount,
+            otherchain,
+            otherchain_wallet,
+            whitelist_proof,
+         };
+         back2project(deps, env, sender, fund, accepted, params)
+      }
+      ReceiveMsg::SubmitProject { project_info } => {
+         //-----------only bond token----------------------------------
+         let bond = check_bond(&config, &[fund], true)?;
+         submit_project(deps, env, sender, project_info, bond, true)
+      }
+   }
+}
 
-      backer_states: Vec::new(),
+//---------config denom is always accepted with rate 1------------------------------
+pub fn load_accepted_denom(
+   store: &dyn Storage,
+   config: &Config,
+   denom: &str,
+   is_cw20: bool,
+) -> Result<AcceptedDenom, ContractError> {
+   if !is_cw20 && denom == config.denom {
+      return Ok(AcceptedDenom {
+         denom: denom.to_string(),
+         is_cw20: false,
+         rate: Decimal::one(),
+      });
+   }
+   match ACCEPTED_DENOMS.may_load(store, denom)? {
+      Some(accepted) if accepted.is_cw20 == is_cw20 => Ok(accepted),
+      _ => Err(ContractError::NotAcceptedDenom {
+         denom: denom.to_string(),
+      }),
+   }
+}
 
-      project_milestonestep: Uint128::zero(), //first milestonestep
+pub fn check_fee_bps(fee_bps: Uint64) -> Result<u64, ContractError> {
+   if fee_bps.u64() > FEE_BPS_DENOMINATOR {
+      return Err(ContractError::InvalidFee {});
+   }
+   Ok(fee_bps.u64())
+}
 
-      whitelist: Vec::new(),
-      holder_alloc: Uint128::from(80u128),
-      holder_ticket: Uint128::zero(),
-      community_ticket: Uint128::zero(),
-      //-------------------------------------------
-      creator_wallet: deps.api.addr_validate(&_creator_wallet).unwrap(),
-      project_collected: _project_collected,
-
-      milestone_states: _project_milestones,
-      teammember_states: _project_teammembers,
-      vesting: _vesting.clone(),
-      token_addr: token_addr.clone(),
-      //---------------------------------------------------
-      country: _country,
-      cofounder_name: _cofounder_name,
-      service_wefund: _service_wefund,
-      service_charity: _service_charity,
-      professional_link: _professional_link,
-   };
+pub fn check_fee_recipients(
+   deps: Deps,
+   fee_recipients: Vec<FeeRecipient>,
+) -> Result<Vec<FeeRecipient>, ContractError> {
+   let mut total_weight = Uint64::zero();
+   for recipient in fee_recipients.iter() {
+      deps.api.addr_validate(&recipient.address)?;
+      total_weight = total_weight
+         .checked_add(recipient.weight)
+         .map_err(StdError::from)?;
+   }
+   if !fee_recipients.is_empty() && total_weight.is_zero() {
+      return Err(ContractError::InvalidFee {});
+   }
+   Ok(fee_recipients)
+}
 
-   if _project_id == Uint64::zero() {
-      save_projectstate(deps.storage, &mut new_project)?;
-   } else {
-      let x = PROJECTSTATES.load(deps.storage, _project_id.u64())?;
-      new_project.project_id = x.project_id;
-      new_project.project_status = x.project_status;
-      new_project.fundraising_stage = x.fundraising_stage;
-      new_project.backerbacked_amount = x.backerbacked_amount;
-      new_project.backer_states = x.backer_states;
-      new_project.project_milestonestep = x.project_milestonestep;
-      new_project.whitelist = x.whitelist;
-      new_project.holder_alloc = x.holder_alloc;
-      new_project.holder_ticket = x.holder_ticket;
-      new_project.community_ticket = x.community_ticket;
-      PROJECTSTATES.save(deps.storage, _project_id.u64(), &new_project)?;
+//---------fee is rounded down, the rest of the amount is escrowed-------------------
+pub fn calc_fee(amount: Uint128, fee_bps: u64) -> Uint128 {
+   amount.multiply_ratio(fee_bps, FEE_BPS_DENOMINATOR)
+}
+
+//---------block time must be in [start, end) when period is set-----------------
+pub fn check_period(env: &Env, period: &Option<Period>) -> Result<(), ContractError> {
+   if let Some(period) = period {
+      let now = env.block.time.seconds();
+      if now < period.start.u64() || now >= period.end.u64() {
+         return Err(ContractError::OutOfPeriod {
+            start: period.start,
+            end: period.end,
+         });
+      }
    }
+   Ok(())
+}
 
-   let config = CONFIG.load(deps.storage)?;
-   if config.vesting_contract != "".to_string() {
-      let mut vesting_params: Vec<VestingParam> = Vec::new();
-      for param in _vesting {
-         vesting_params.push(VestingParam {
-            soon: param.stage_soon,
-            after: param.stage_after,
-            period: param.stage_period,
-         })
+//---------amount * numerator / denominator, rounded up----------------------------
+pub fn multiply_ratio_ceil(
+   amount: Uint128,
+   numerator: Uint128,
+   denominator: Uint128,
+) -> StdResult<Uint128> {
+   let floor = amount.multiply_ratio(numerator, denominator);
+   if floor.full_mul(denominator) < amount.full_mul(numerator) {
+      return Ok(floor.checked_add(Uint128::new(1))?);
+   }
+   Ok(floor)
+}
+
+//---------split fee between recipients by weight, rounding dust to the first one----
+pub fn fee_msgs(store: &dyn Storage, config: &Config, fee: Coin) -> StdResult<Vec<CosmosMsg>> {
+   let mut msgs: Vec<CosmosMsg> = Vec::new();
+   if fee.amount.is_zero() {
+      return Ok(msgs);
+   }
+   if config.fee_recipients.is_empty() {
+      msgs.push(transfer_fund(store, fee, &config.wefund)?);
+      return Ok(msgs);
+   }
+
+   let total_weight: u64 = config.fee_recipients.iter().map(|x| x.weight.u64()).sum();
+   let mut shares: Vec<Uint128> = config
+      .fee_recipients
+      .iter()
+      .map(|x| fee.amount.multiply_ratio(x.weight.u64(), total_weight))
+      .collect();
+   let distributed: Uint128 = shares.iter().sum();
+   shares[0] += fee.amount - distributed;
+
+   for (recipient, share) in config.fee_recipients.iter().zip(shares) {
+      if share.is_zero() {
+         continue;
       }
-      //----------add fundraising project------------------------
-      let add_vesting_project = WasmMsg::Execute {
-         contract_addr: config.vesting_contract.to_string(),
-         msg: to_binary(&VestingMsg::AddProject {
-            project_id: new_project.project_id,
-            admin: _env.contract.address.to_string(),
-            token_addr: token_addr.to_string(),
-            vesting_params: vesting_params,
-            start_time: Uint128::zero(),
-         })
-         .unwrap(),
-         funds: vec![],
+      let fund = Coin {
+         denom: fee.denom.clone(),
+         amount: share,
       };
+      msgs.push(transfer_fund(
+         store,
+         fund,
+         &Addr::unchecked(&recipient.address),
+      )?);
+   }
+   Ok(msgs)
+}
+
+pub fn default_tier_weights() -> TierWeights {
+   TierWeights {
+      platium: Uint128::new(120),
+      gold: Uint128::new(50),
+      silver: Uint128::new(11),
+      bronze: Uint128::new(1),
+   }
+}
+
+pub fn card_weight(weights: &TierWeights, card_type: &CardType) -> Uint128 {
+   match card_type {
+      CardType::Platium => weights.platium,
+      CardType::Gold => weights.gold,
+      CardType::Silver => weights.silver,
+      CardType::Bronze => weights.bronze,
+      CardType::Other => Uint128::zero(),
+   }
+}
+
+pub fn calc_allocation(x: &ProjectState, card_type: &CardType) -> Uint128 {
+   match card_type {
+      CardType::Other => x.community_ticket,
+      _ => x.holder_ticket * card_weight(&x.tier_weights, card_type),
+   }
+}
+
+//---------split unfilled target in holder and community pools, empty pool goes to the other----
+pub fn calc_tickets(
+   x: &ProjectState,
+   decimals: u32,
+   community_count: usize,
+) -> AllocationPreviewResponse {
+   let target = x.project_collected * Uint128::from(10u128.pow(decimals));
+   let backamount = target.saturating_sub(backed_total(x));
+   let community_count = Uint128::from(community_count as u128);
+
+   let mut holder_pool = backamount.multiply_ratio(x.holder_alloc, 100u128);
+   let mut community_pool = backamount - holder_pool;
+   if x.whitelist_weight.is_zero() {
+      community_pool += holder_pool;
+      holder_pool = Uint128::zero();
+   } else if community_count.is_zero() {
+      holder_pool += community_pool;
+      community_pool = Uint128::zero();
+   }
+
+   AllocationPreviewResponse {
+      holder_pool,
+      community_pool,
+      whitelist_weight: x.whitelist_weight,
+      community_count,
+      holder_ticket: holder_pool
+         .checked_div(x.whitelist_weight)
+         .unwrap_or_default(),
+      community_ticket: community_pool
+         .checked_div(community_count)
+         .unwrap_or_default(),
+   }
+}
+
+//---------whitelist entry with allocation, community members at close join later---
+pub fn load_whitelist(
+   store: &dyn Storage,
+   x: &ProjectState,
+   wallet: &Addr,
+) -> StdResult<Option<WhitelistState>> {
+   let mut whitelist = WHITELIST.may_load(store, (x.project_id.u64(), wallet))?;
+   if whitelist.is_none()
+      && WHITELIST_COMMUNITY
+         .may_load(store, x.project_id.u64())?
+         .unwrap_or_default()
+         .contains(wallet)
+   {
+      whitelist = Some(WhitelistState {
+         wallet: wallet.clone(),
+         card_type: CardType::Other,
+         allocation: Uint128::zero(),
+         backed: Uint128::zero(),
+      });
+   }
+   Ok(whitelist.map(|mut one| {
+      one.allocation = calc_allocation(x, &one.card_type);
+      one
+   }))
+}
+
+//---------registered and merkle entries of a wallet, one cap on the larger----------
+fn combine_allocation(
+   registered: Option<WhitelistState>,
+   proven: Option<WhitelistState>,
+) -> Option<WhitelistState> {
+   match (registered, proven) {
+      (Some(mut one), Some(other)) => {
+         one.allocation = one.allocation.max(other.allocation);
+         one.backed += other.backed;
+         Some(one)
+      }
+      (one, other) => one.or(other),
+   }
+}
+
+//---------allocation of a wallet over both whitelists---------------------------
+pub fn load_allocation(
+   store: &dyn Storage,
+   x: &ProjectState,
+   wallet: &Addr,
+) -> StdResult<Option<WhitelistState>> {
+   let registered = load_whitelist(store, x, wallet)?;
+   let proven = MERKLE_WHITELIST.may_load(store, (x.project_id.u64(), wallet))?;
+   Ok(combine_allocation(registered, proven))
+}
+
+//---------position of a backing, token id is set when saved-----------------------
+fn new_position(
+   x: &ProjectState,
+   backer: &BackerState,
+   backed_amount: Uint128,
+   stage: Uint128,
+   token_amount: Uint128,
+   crosschain: bool,
+) -> Position {
+   Position {
+      token_id: Uint64::zero(), //auto increment
+      owner: backer.backer_wallet.clone(),
+      project_id: x.project_id,
+      stage,
+      amount: backer.amount.clone(),
+      backed: backed_amount,
+      token_amount,
+      weight: backed_amount,
+      voted_round: Uint64::zero(),
+      refunded: false,
+      crosschain,
+      approvals: Vec::new(),
+   }
+}
+
+//---------record backing of whitelisted wallet, storage per backer stays constant----
+fn add_backer(
+   store: &mut dyn Storage,
+   x: &mut ProjectState,
+   backer: BackerState,
+   position: Position,
+   whitelist_proof: Option<WhitelistProof>,
+) -> Result<Position, ContractError> {
+   let wallet = backer.backer_wallet.clone();
+   let backed_amount = position.backed;
+   match (&x.project_status, whitelist_proof) {
+      //-----------public sale is open to any wallet up to the cap-------------
+      (ProjectStatus::PublicSale, _) => {
+         let key = (x.project_id.u64(), &wallet);
+         let backed = PUBLIC_BACKED.may_load(store, key)?.unwrap_or_default();
+         check_public_cap(x, backed, backed_amount)?;
+         PUBLIC_BACKED.save(store, key, &(backed + backed_amount))?;
+      }
+      //-----------registered and merkle entries share one cap per wallet--------
+      (_, proof) => {
+         let key = (x.project_id.u64(), &wallet);
+         let registered = load_whitelist(store, x, &wallet)?;
+         let mut proven = MERKLE_WHITELIST.may_load(store, key)?;
+         let with_proof = proof.is_some();
+         if let Some(proof) = proof {
+            let root = MERKLE_ROOTS
+               .may_load(store, x.project_id.u64())?
+               .ok_or(ContractError::NoMerkleRoot {})?;
+            let leaf = leaf_hash(wallet.as_str(), &proof.card_type, proof.allocation);
+            if !verify_proof(&root, &leaf, &proof.proof) {
+               return Err(ContractError::InvalidMerkleProof {});
+            }
+            proven = Some(WhitelistState {
+               wallet: wallet.clone(),
+               card_type: proof.card_type,
+               allocation: proof.allocation,
+               backed: proven.map(|one| one.backed).unwrap_or_default(),
+            });
+         }
+         let combined = combine_allocation(registered.clone(), proven.clone())
+            .ok_or(ContractError::NotRegisteredWhitelist {})?;
+         check_allocation(&combined, backed_amount)?;
+
+         //-----------backing counts on the merkle entry when proven with it------
+         match (registered, proven) {
+            (Some(mut one), _) if !with_proof => {
+               one.backed += backed_amount;
+               WHITELIST.save(store, key, &one)?;
+            }
+            (_, Some(mut one)) => {
+               one.backed += backed_amount;
+               MERKLE_WHITELIST.save(store, key, &one)?;
+            }
+            _ => return Err(ContractError::NotRegisteredWhitelist {}),
+         }
+      }
+   }
+
+   //-----------every position votes on milestones with its backed amount-------
+   //-----------only funds received here go to escrow-----------------------
+   x.milestone_total_weight += backed_amount;
+   if position.crosschain {
+      x.crosschain_backed_amount += backed_amount;
+   } else {
+      x.backerbacked_amount += backed_amount;
+      add_coin(&mut x.backerbacked_funds, &backer.amount);
+      add_coin(&mut x.backerbacked_total_funds, &backer.amount);
+   }
+
+   //-----------each backing is a position held by backer wallet------------
+   let mut position = position;
+   save_position(store, &mut position)?;
 
-      return Ok(Response::new()
-         .add_messages(vec![CosmosMsg::Wasm(add_vesting_project)])
-         .add_attribute("action", "add project")
-         .add_attribute("id", new_project.project_id));
+   let mut backings = BACKERS
+      .may_load(store, (x.project_id.u64(), &wallet))?
+      .unwrap_or_default();
+   if backings.is_empty() {
+      x.backer_count += Uint64::new(1);
    }
+   backings.push(backer);
+   BACKERS.save(store, (x.project_id.u64(), &wallet), &backings)?;
+   Ok(position)
+}
 
-   Ok(Response::new().add_attribute("action", "add project"))
+fn backing_event(position: &Position) -> Event {
+   Event::new(EVENT_BACKING)
+      .add_attribute(ATTR_PROJECT_ID, position.project_id.to_string())
+      .add_attribute(ATTR_WALLET, position.owner.to_string())
+      .add_attribute(ATTR_TOKEN_ID, position.token_id.to_string())
+      .add_attribute(ATTR_AMOUNT, position.amount.to_string())
+      .add_attribute(ATTR_BACKED, position.backed.to_string())
+      .add_attribute(ATTR_STAGE, position.stage.to_string())
+      .add_attribute(ATTR_TOKEN_AMOUNT, position.token_amount.to_string())
 }
 
-pub fn try_back2projectwithout(
-   deps: DepsMut,
-   env: Env,
-   info: MessageInfo,
-   project_id: Uint64,
-   backer_wallet: String,
-   denom: String,
+//---------public backing is capped per wallet-----------------------------------------
+fn check_public_cap(
+   x: &ProjectState,
+   backed: Uint128,
    amount: Uint128,
-   fundraising_stage: Uint128,
-   token_amount: Uint128,
-   otherchain: String,
-   otherchain_wallet: String,
-) -> Result<Response, ContractError> {
-   //-------check project exist-----------------------------------
-   let res = PROJECTSTATES.may_load(deps.storage, project_id.u64());
-   if res == Ok(None) {
-      //not exist
-      return Err(ContractError::NotRegisteredProject {});
+) -> Result<(), ContractError> {
+   let cap = x
+      .public_sale
+      .as_ref()
+      .map(|x| x.wallet_cap)
+      .unwrap_or_default();
+   if backed + amount > cap {
+      return Err(ContractError::PublicCapExceeded {
+         cap,
+         backed,
+         amount,
+      });
    }
-   //--------Get project info------------------------------------
-   let mut x = PROJECTSTATES.load(deps.storage, project_id.u64())?;
-   let config = CONFIG.load(deps.storage)?;
-   let fund = Coin {
-      denom: denom,
-      amount: amount
-   };
-   let mut fund_real_back = fund.clone();
-   let mut fund_wefund = fund.clone();
-
-   //--------calc amount to desposit and to wefund
-   fund_real_back.amount = Uint128::new(fund.amount.u128() * 95 / 100);
-   fund_wefund.amount = Uint128::new(fund.amount.u128() * 5 / 100);
+   Ok(())
+}
 
-   let backer_wallet = deps.api.addr_validate(&backer_wallet)?;
+//---------backing is capped at wallet's remaining allocation------------------------
+pub fn check_allocation(whitelist: &WhitelistState, amount: Uint128) -> Result<(), ContractError> {
+   let remaining = whitelist.allocation.saturating_sub(whitelist.backed);
+   if amount > remaining {
+      return Err(ContractError::AllocationExceeded {
+         allocation: whitelist.allocation,
+         backed: whitelist.backed,
+         amount,
+      });
+   }
+   Ok(())
+}
 
-   //-----sum in whitelist-------------------
-   let index = x
-      .whitelist
-      .iter()
-      .position(|x| x.wallet == backer_wallet.clone());
-   if index == None {
-      return Err(ContractError::NotRegisteredWhitelist {});
+pub fn add_coin(coins: &mut Vec<Coin>, fund: &Coin) {
+   match coins.iter_mut().find(|x| x.denom == fund.denom) {
+      Some(coin) => coin.amount += fund.amount,
+      None => coins.push(fund.clone()),
    }
+}
+
+pub fn coins_to_string(coins: &[Coin]) -> String {
+   let coins: Vec<String> = coins.iter().map(|x| x.to_string()).collect();
+   coins.join(",")
+}
 
-   x.whitelist[index.unwrap()].backed += fund_real_back.amount;
-   x.backerbacked_amount += fund_real_back.amount;
+//---------backed toward the target, here and attested on other chains---------------
+pub fn backed_total(x: &ProjectState) -> Uint128 {
+   x.backerbacked_amount + x.crosschain_backed_amount
+}
 
-   let new_baker: BackerState = BackerState {
-      backer_wallet: backer_wallet.clone(),
-      otherchain: otherchain,
-      otherchain_wallet: otherchain_wallet,
-      amount: fund_real_back.clone(),
-   };
+//---------part of a release paid from escrow here, the rest is on other chains------
+fn escrow_share(x: &ProjectState, amount: Uint128) -> Uint128 {
+   //-----every position weighs its backed amount, so total weight is all backing---
+   let total = x.milestone_total_weight;
+   if x.crosschain_backed_amount.is_zero() || total.is_zero() {
+      return amount;
+   }
+   amount.multiply_ratio(total.saturating_sub(x.crosschain_backed_amount), total)
+}
 
-   x.backer_states.push(new_baker);
+//---------take amount in config denom unit out of escrow, pro-rata over denoms------
+pub fn take_escrow(x: &mut ProjectState, amount: Uint128) -> Vec<Coin> {
+   let mut funds: Vec<Coin> = Vec::new();
+   if x.backerbacked_amount.is_zero() {
+      return funds;
+   }
 
-   PROJECTSTATES.update(deps.storage, project_id.u64(), |op| match op {
-      None => Err(ContractError::NotRegisteredProject {}),
-      Some(mut project) => {
-         project.project_status = x.project_status.clone();
-         project.backerbacked_amount = x.backerbacked_amount;
-         project.backer_states = x.backer_states;
-         project.whitelist = x.whitelist;
-
-         if x.project_status == ProjectStatus::Releasing {
-            //only on switching releasing status
-            project.milestone_states = x.milestone_states;
-         }
-         Ok(project)
+   let take_all = amount >= x.backerbacked_amount;
+   for fund in x.backerbacked_funds.iter_mut() {
+      let take = match take_all {
+         true => fund.amount,
+         false => fund.amount.multiply_ratio(amount, x.backerbacked_amount),
+      };
+      if !take.is_zero() {
+         fund.amount -= take;
+         funds.push(Coin {
+            denom: fund.denom.clone(),
+            amount: take,
+         });
       }
-   })?;
+   }
+   x.backerbacked_funds.retain(|x| !x.amount.is_zero());
+   x.backerbacked_amount = x.backerbacked_amount.saturating_sub(amount);
 
-   Ok(Response::new()
-      .add_attribute("action", "back to project without"))
+   funds
 }
 
-pub fn try_back2project(
+//---------bank send for native coins, cw20 transfer for tokens accepted once-------
+pub fn transfer_fund(store: &dyn Storage, fund: Coin, recipient: &Addr) -> StdResult<CosmosMsg> {
+   let is_cw20 = CW20_TOKENS.has(store, &fund.denom);
+   send_fund(fund, is_cw20, recipient)
+}
+
+pub fn send_fund(fund: Coin, is_cw20: bool, recipient: &Addr) -> StdResult<CosmosMsg> {
+   if is_cw20 {
+      return Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+         contract_addr: fund.denom,
+         msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: fund.amount,
+         })?,
+         funds: vec![],
+      }));
+   }
+
+   Ok(CosmosMsg::Bank(BankMsg::Send {
+      to_address: recipient.to_string(),
+      amount: vec![fund],
+   }))
+}
+
+fn back2project(
    deps: DepsMut,
    env: Env,
-   info: MessageInfo,
-   project_id: Uint64,
-   backer_wallet: String,
-   fundraising_stage: Uint128,
-   token_amount: Uint128,
-   otherchain: String,
-   otherchain_wallet: String,
+   sender: Addr,
+   fund: Coin,
+   accepted: AcceptedDenom,
+   params: BackParams,
 ) -> Result<Response, ContractError> {
+   let BackParams {
+      project_id,
+      backer_wallet,
+      fundraising_stage,
+      token_amount,
+      otherchain,
+      otherchain_wallet,
+      whitelist_proof,
+   } = params;
    //-------check project exist-----------------------------------
-   let res = PROJECTSTATES.may_load(deps.storage, project_id.u64());
+   let res = projectstates().may_load(deps.storage, project_id.u64());
    if res == Ok(None) {
       //not exist
       return Err(ContractError::NotRegisteredProject {});
    }
    //--------Get project info------------------------------------
-   let mut x = PROJECTSTATES.load(deps.storage, project_id.u64())?;
-   if x.project_status != ProjectStatus::Fundraising {
+   let mut x = projectstates().load(deps.storage, project_id.u64())?;
+   if !is_fundraising(&x) {
       //only fundraising status
       return Err(ContractError::NotCorrectStatus {
          status: x.project_status as u32,
       });
    }
+   check_period(&env, &x.fundraising_period)?;
 
    let config = CONFIG.load(deps.storage)?;
-   //--------check sufficient back--------------------
-   if info.funds.is_empty() {
-      return Err(ContractError::NeedCoin {});
-   }
-   let fund = info.funds[0].clone();
+   let mut fund = fund;
+   let mut token_amount = token_amount;
    let mut fund_real_back = fund.clone();
    let mut fund_wefund = fund.clone();
 
    //--------calc amount to desposit and to wefund
-   fund_real_back.amount = Uint128::new(fund.amount.u128() * 95 / 100);
-   fund_wefund.amount = Uint128::new(fund.amount.u128() * 5 / 100);
+   let fee_bps = x.fee_bps.map(|x| x.u64()).unwrap_or(config.fee_bps);
+   fund_wefund.amount = calc_fee(fund.amount, fee_bps);
+   fund_real_back.amount = fund.amount - fund_wefund.amount;
 
    let backer_wallet = deps.api.addr_validate(&backer_wallet)?;
 
    //--------check backed amount----------------
-   let collected = x.project_collected * Uint128::from((10u128).pow(config.decimals));
+   let collected = collected_target(&config, &x);
+
+   //--------cap at remaining target, surplus goes back to sender------
+   let remaining = collected.saturating_sub(backed_total(&x));
+   let mut surplus = fund.clone();
+   surplus.amount = Uint128::zero();
+   let backed_amount = fund_real_back.amount * accepted.rate;
+   if backed_amount > remaining {
+      let real_back = multiply_ratio_ceil(fund_real_back.amount, remaining, backed_amount)?;
+      fund_wefund.amount = fund_wefund
+         .amount
+         .multiply_ratio(real_back, fund_real_back.amount);
+      fund_real_back.amount = real_back;
+      let accepted_amount = fund_real_back.amount + fund_wefund.amount;
+      surplus.amount = fund.amount - accepted_amount;
+      token_amount = token_amount.multiply_ratio(accepted_amount, fund.amount);
+      fund.amount = accepted_amount;
+   }
 
    // if x.backerbacked_amount >= collected{
    //     return Err(ContractError::AlreadyCollected{});
    // }
-   //-----sum in whitelist-------------------
-   let index = x
-      .whitelist
-      .iter()
-      .position(|x| x.wallet == backer_wallet.clone());
-   if index == None {
-      return Err(ContractError::NotRegisteredWhitelist {});
-   }
-
-   x.whitelist[index.unwrap()].backed += fund_real_back.amount;
-   x.backerbacked_amount += fund_real_back.amount;
-
+   //-----backed amount in config denom unit-------------
+   let backed_amount = fund_real_back.amount * accepted.rate;
    let new_baker: BackerState = BackerState {
       backer_wallet: backer_wallet.clone(),
       otherchain: otherchain,
       otherchain_wallet: otherchain_wallet,
       amount: fund_real_back.clone(),
    };
+   let position = new_position(
+      &x,
+      &new_baker,
+      backed_amount,
+      fundraising_stage,
+      token_amount,
+      false,
+   );
+   let position = add_backer(deps.storage, &mut x, new_baker, position, whitelist_proof)?;
 
-   x.backer_states.push(new_baker);
+   let (mut msgs, events) = release_if_collected(deps.as_ref(), &env, &mut x, collected)?;
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
 
-   //------check needback-----------------
-   let mut backer_needback = true;
+   //---------send fee to Wefund and fee recipients--------------------
+   msgs.extend(fee_msgs(deps.storage, &config, fund_wefund)?);
 
-   if x.backerbacked_amount >= collected {
-      backer_needback = false;
+   //---------refund overfunding------------------------------
+   if !surplus.amount.is_zero() {
+      msgs.push(transfer_fund(deps.storage, surplus.clone(), &sender)?);
    }
 
-   let mut msgs: Vec<CosmosMsg> = vec![];
+   msgs.extend(vesting_adduser_msgs(&config, &position)?);
 
-   //---------check collection and switch to releasing status---------
-   if backer_needback == false {
-      x.project_status = ProjectStatus::Releasing; //releasing
-
-      //------add milestone votes in every milestone---------------
-      let community = COMMUNITY.load(deps.storage)?;
-      let mut milestone_votes = Vec::new();
-      for backer in x.backer_states.clone() {
-         let index = community.iter().position(|x| x == &backer.backer_wallet);
-         if index == None {
-            milestone_votes.push(Vote {
-               wallet: backer.backer_wallet,
-               voted: false,
-            });
-         }
-      }
-      //-----add wefund vote------------------
-      let config = CONFIG.load(deps.storage)?;
-      milestone_votes.push(Vote {
-         wallet: config.owner,
-         voted: true,
-      });
+   Ok(Response::new()
+      .add_messages(msgs)
+      .add_event(backing_event(&position))
+      .add_events(events)
+      .add_attribute("action", "back to project")
+      .add_attribute("token_id", position.token_id)
+      .add_attribute("accepted", fund.to_string())
+      .add_attribute("surplus", surplus.to_string()))
+}
 
-      for i in 0..(x.milestone_states.len() as usize) {
-         x.milestone_states[i].milestone_votes = milestone_votes.clone();
-      }
+//---------target of the project in config denom unit----------------------------
+fn collected_target(config: &Config, x: &ProjectState) -> Uint128 {
+   x.project_collected * Uint128::from((10u128).pow(config.decimals))
+}
 
-      if config.vesting_contract != "".to_string() && x.token_addr != "".to_string() {
-         let vesting = x.vesting.clone();
-         let mut token_amount = Uint128::zero();
-         for stage in vesting {
-            token_amount += stage.stage_amount;
-         }
+//---------switch to releasing once backing reached the target--------------------
+fn release_if_collected(
+   deps: Deps,
+   env: &Env,
+   x: &mut ProjectState,
+   collected: Uint128,
+) -> Result<(Vec<CosmosMsg>, Vec<Event>), ContractError> {
+   if backed_total(x) < collected {
+      return Ok((vec![], vec![]));
+   }
+   let (msgs, event) = start_releasing(deps, env, x)?;
+   Ok((msgs, vec![event]))
+}
 
-         let token_info: TokenInfoResponse = deps
-            .querier
-            .query_wasm_smart(x.token_addr.clone(), &Cw20QueryMsg::TokenInfo {})?;
-
-         token_amount = token_amount * Uint128::new((10 as u128).pow(token_info.decimals as u32)); //for decimals
-         let token_transfer = WasmMsg::Execute {
-            contract_addr: x.token_addr.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
-               owner: x.creator_wallet.to_string(),
-               recipient: config.vesting_contract.to_string(),
-               amount: token_amount,
-            })
-            .unwrap(),
-            funds: vec![],
-         };
-         msgs.push(CosmosMsg::Wasm(token_transfer));
-         //---------start vesting-----------------------------
-         let start_vesting = WasmMsg::Execute {
-            contract_addr: config.vesting_contract.to_string(),
-            msg: to_binary(&VestingMsg::StartRelease {
-               project_id: x.project_id,
-               start_time: Uint128::from(env.block.time.seconds()),
-            })
-            .unwrap(),
-            funds: vec![],
-         };
-         msgs.push(CosmosMsg::Wasm(start_vesting));
-      }
+//---------add fundraising user, holder of the position---------------------------
+fn vesting_adduser_msgs(config: &Config, position: &Position) -> StdResult<Vec<CosmosMsg>> {
+   if config.vesting_contract == "".to_string() {
+      return Ok(vec![]);
    }
+   Ok(vec![CosmosMsg::Wasm(WasmMsg::Execute {
+      contract_addr: config.vesting_contract.to_string(),
+      msg: to_binary(&VestingMsg::AddUser {
+         project_id: position.project_id,
+         wallet: position.owner.clone(),
+         stage: position.stage,
+         amount: position.token_amount,
+      })?,
+      funds: vec![],
+   })])
+}
 
-   PROJECTSTATES.update(deps.storage, project_id.u64(), |op| match op {
-      None => Err(ContractError::NotRegisteredProject {}),
-      Some(mut project) => {
-         project.project_status = x.project_status.clone();
-         project.backerbacked_amount = x.backerbacked_amount;
-         project.backer_states = x.backer_states;
-         project.whitelist = x.whitelist;
-
-         if x.project_status == ProjectStatus::Releasing {
-            //only on switching releasing status
-            project.milestone_states = x.milestone_states;
-         }
-         Ok(project)
-      }
-   })?;
+//---------switch to releasing, open milestone voting and start vesting------------
+fn start_releasing(
+   deps: Deps,
+   env: &Env,
+   x: &mut ProjectState,
+) -> Result<(Vec<CosmosMsg>, Event), ContractError> {
+   let config = CONFIG.load(deps.storage)?;
+   let mut msgs: Vec<CosmosMsg> = vec![];
+   let event = transition_project(x, ProjectStatus::Releasing)?; //releasing
 
-   //---------send to Wefund with 5/100--------------------
-   let bank_wefund = BankMsg::Send {
-      to_address: config.wefund.to_string(),
-      amount: vec![fund_wefund],
-   };
-   msgs.push(CosmosMsg::Bank(bank_wefund));
+   //------open voting on first milestone, holders vote with backed amount------
+   open_milestone_vote(x, env);
 
-   if config.vesting_contract != "".to_string() {
-      //----------add fundraising user------------------------
-      let add_fundraising_user = WasmMsg::Execute {
-         contract_addr: config.vesting_contract.to_string(),
-         msg: to_binary(&VestingMsg::AddUser {
-            project_id: project_id,
-            wallet: info.sender,
-            stage: fundraising_stage,
+   if config.vesting_contract != "".to_string() && x.token_addr != "".to_string() {
+      let vesting = x.vesting.clone();
+      let mut token_amount = Uint128::zero();
+      for stage in vesting {
+         token_amount += stage.stage_amount;
+      }
+
+      let token_info: TokenInfoResponse = deps
+         .querier
+         .query_wasm_smart(x.token_addr.clone(), &Cw20QueryMsg::TokenInfo {})?;
+
+      token_amount = token_amount * Uint128::new((10 as u128).pow(token_info.decimals as u32)); //for decimals
+      let token_transfer = WasmMsg::Execute {
+         contract_addr: x.token_addr.to_string(),
+         msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+            owner: x.creator_wallet.to_string(),
+            recipient: config.vesting_contract.to_string(),
             amount: token_amount,
          })
          .unwrap(),
          funds: vec![],
       };
-      msgs.push(CosmosMsg::Wasm(add_fundraising_user));
+      msgs.push(CosmosMsg::Wasm(token_transfer));
+      //---------start vesting-----------------------------
+      let start_vesting = WasmMsg::Execute {
+         contract_addr: config.vesting_contract.to_string(),
+         msg: to_binary(&VestingMsg::StartRelease {
+            project_id: x.project_id,
+            start_time: Uint128::from(env.block.time.seconds()),
+         })
+         .unwrap(),
+         funds: vec![],
+      };
+      msgs.push(CosmosMsg::Wasm(start_vesting));
+   }
+   Ok((msgs, event))
+}
+
+//---------backing is open in guaranteed and public rounds---------------------------
+pub fn is_fundraising(x: &ProjectState) -> bool {
+   x.project_status == ProjectStatus::Fundraising || x.project_status == ProjectStatus::PublicSale
+}
+
+//---------anyone opens public round once guaranteed allocations lapsed-------------
+pub fn try_openpublicsale(
+   deps: DepsMut,
+   env: Env,
+   project_id: Uint64,
+) -> Result<Response, ContractError> {
+   let mut x = projectstates().load(deps.storage, project_id.u64())?;
+   if x.project_status != ProjectStatus::Fundraising {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   let public_sale = x
+      .public_sale
+      .clone()
+      .ok_or(ContractError::NoPublicSale {})?;
+   if env.block.time.seconds() < public_sale.guaranteed_end.u64() {
+      return Err(ContractError::GuaranteedNotEnded {});
+   }
+
+   let event = transition_project(&mut x, ProjectStatus::PublicSale)?;
+   x.public_start = Uint64::new(env.block.time.seconds());
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+
+   Ok(Response::new()
+      .add_event(event)
+      .add_attribute("action", "open public sale"))
+}
+
+pub fn try_finalizefundraising(
+   deps: DepsMut,
+   env: Env,
+   project_id: Uint64,
+) -> Result<Response, ContractError> {
+   let mut x = projectstates().load(deps.storage, project_id.u64())?;
+   if !is_fundraising(&x) {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   //-----------anyone can finalize after fundraising period ended---------
+   match x.fundraising_period.clone() {
+      Some(period) if env.block.time.seconds() >= period.end.u64() => {}
+      _ => return Err(ContractError::FundraisingNotEnded {}),
    }
 
+   let config = CONFIG.load(deps.storage)?;
+   let softcap = x.project_softcap * Uint128::from((10u128).pow(config.decimals));
+   let (msgs, event) = if backed_total(&x) >= softcap {
+      start_releasing(deps.as_ref(), &env, &mut x)?
+   } else {
+      //---------refunds are claimable on fail status------------
+      (vec![], transition_project(&mut x, ProjectStatus::Fail)?)
+   };
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+
    Ok(Response::new()
       .add_messages(msgs)
-      .add_attribute("action", "back to project"))
+      .add_event(event)
+      .add_attribute("action", "finalize fundraising")
+      .add_attribute("status", (x.project_status as u32).to_string()))
+}
+
+//---------round settings given with OpenWhitelist------------------------------
+pub struct WhitelistParams {
+   pub holder_alloc: Uint128,
+   pub tier_weights: Option<TierWeights>,
+   pub lottery: Option<LotterySlots>,
+   pub whitelist_period: Option<Period>,
+   pub fundraising_period: Option<Period>,
+   pub public_sale: Option<PublicSale>,
 }
 
 pub fn try_openwhitelist(
@@ -1030,17 +3131,99 @@ pub fn try_openwhitelist(
    env: Env,
    info: MessageInfo,
    project_id: Uint64,
-   holder_alloc: Uint128,
+   params: WhitelistParams,
 ) -> Result<Response, ContractError> {
-   let mut x = PROJECTSTATES.load(deps.storage, project_id.u64())?;
+   let WhitelistParams {
+      holder_alloc,
+      tier_weights,
+      lottery,
+      whitelist_period,
+      fundraising_period,
+      public_sale,
+   } = params;
+   let mut x = projectstates().load(deps.storage, project_id.u64())?;
    if info.sender != x.creator_wallet {
       return Err(ContractError::Unauthorized {});
    }
-   x.project_status = ProjectStatus::Whitelist;
-   x.whitelist = Vec::new();
+   if x.project_status != ProjectStatus::Whitelist {
+      //only after wefund approve
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   //-----------periods must be ordered, fundraising after whitelist-------
+   for period in [&whitelist_period, &fundraising_period]
+      .iter()
+      .copied()
+      .flatten()
+   {
+      if period.start >= period.end {
+         return Err(ContractError::InvalidPeriod {});
+      }
+   }
+   if let (Some(whitelist), Some(fundraising)) = (&whitelist_period, &fundraising_period) {
+      if whitelist.end > fundraising.start {
+         return Err(ContractError::InvalidPeriod {});
+      }
+   }
+   //-----------public sale opens inside fundraising, after whitelist----------
+   if let Some(public_sale) = &public_sale {
+      let end = public_sale.guaranteed_end;
+      let before_whitelist_end = matches!(&whitelist_period, Some(period) if end < period.end);
+      let outside_fundraising =
+         matches!(&fundraising_period, Some(period) if end < period.start || end >= period.end);
+      if before_whitelist_end || outside_fundraising {
+         return Err(ContractError::InvalidPeriod {});
+      }
+   }
+   if holder_alloc > Uint128::new(100) {
+      return Err(ContractError::InvalidHolderAlloc {});
+   }
+   //-----------registered weights are counted with the tiers they joined on---
+   let tier_weights = tier_weights.unwrap_or_else(default_tier_weights);
+   let registered = WHITELIST
+      .prefix(project_id.u64())
+      .range(deps.storage, None, None, Order::Ascending)
+      .next()
+      .is_some();
+   if registered
+      && (tier_weights != x.tier_weights
+         || lottery.is_some() != LOTTERIES.has(deps.storage, project_id.u64()))
+   {
+      return Err(ContractError::WhitelistRegistered {});
+   }
+   //-----------lottery can't change once seed is committed------------------
+   if let Some(old) = LOTTERIES.may_load(deps.storage, project_id.u64())? {
+      if !old.creator_commit.is_empty() || !old.admin_commit.is_empty() {
+         return Err(ContractError::AlreadyCommitted {});
+      }
+   }
+   match lottery {
+      Some(slots) => LOTTERIES.save(
+         deps.storage,
+         project_id.u64(),
+         &LotteryState {
+            slots,
+            creator_commit: String::new(),
+            admin_commit: String::new(),
+            creator_secret: None,
+            admin_secret: None,
+            seed: String::new(),
+            winners: Vec::new(),
+            reveal_end: Uint64::zero(),
+         },
+      )?,
+      None => LOTTERIES.remove(deps.storage, project_id.u64()),
+   }
    x.holder_alloc = holder_alloc;
-   PROJECTSTATES.save(deps.storage, project_id.u64(), &x)?;
-   Ok(Response::new())
+   x.tier_weights = tier_weights;
+   x.whitelist_period = whitelist_period;
+   x.fundraising_period = fundraising_period;
+   x.public_sale = public_sale;
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+   Ok(Response::new()
+      .add_attribute("action", "open whitelist")
+      .add_attribute("project_id", project_id))
 }
 
 pub fn try_registerwhitelist(
@@ -1048,30 +3231,148 @@ pub fn try_registerwhitelist(
    env: Env,
    info: MessageInfo,
    project_id: Uint64,
-   card_type: CardType,
 ) -> Result<Response, ContractError> {
-   let mut x = PROJECTSTATES.load(deps.storage, project_id.u64())?;
-   let res = x.whitelist.iter().find(|x| x.wallet == info.sender);
-   if res == None {
-      x.whitelist.push(WhitelistState {
-         wallet: info.sender,
-         card_type: card_type,
-         allocation: Uint128::zero(),
-         backed: Uint128::zero(),
+   let mut x = projectstates().load(deps.storage, project_id.u64())?;
+   if x.project_status != ProjectStatus::Whitelist {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
       });
-      PROJECTSTATES.save(deps.storage, project_id.u64(), &x)?;
    }
-   Ok(Response::new())
+   check_period(&env, &x.whitelist_period)?;
+   //-----------no registering once a lottery secret is known----------------
+   if let Some(lottery) = LOTTERIES.may_load(deps.storage, project_id.u64())? {
+      if lottery.creator_secret.is_some() || lottery.admin_secret.is_some() {
+         return Err(ContractError::RegistrationClosed {});
+      }
+   }
+   let mut events: Vec<Event> = Vec::new();
+   if !WHITELIST.has(deps.storage, (project_id.u64(), &info.sender)) {
+      //-----------tier is what wallet has staked, not what it claims-----------
+      let config = CONFIG.load(deps.storage)?;
+      let card_type = query_card_type(deps.as_ref(), &config, &info.sender)?;
+      if card_type == CardType::Other {
+         return Err(ContractError::NoCardTier {});
+      }
+      events.push(
+         Event::new(EVENT_WHITELIST_REGISTERED)
+            .add_attribute(ATTR_PROJECT_ID, project_id.to_string())
+            .add_attribute(ATTR_WALLET, info.sender.to_string())
+            .add_attribute(ATTR_CARD_TYPE, format!("{:?}", card_type)),
+      );
+      x.whitelist_weight += card_weight(&x.tier_weights, &card_type);
+      WHITELIST.save(
+         deps.storage,
+         (project_id.u64(), &info.sender),
+         &WhitelistState {
+            wallet: info.sender.clone(),
+            card_type: card_type,
+            allocation: Uint128::zero(),
+            backed: Uint128::zero(),
+         },
+      )?;
+      projectstates().save(deps.storage, project_id.u64(), &x)?;
+   }
+   Ok(Response::new().add_events(events))
 }
 
-pub fn try_closewhitelist(
+//---------card tier staked by wallet, from staking contract---------------------
+fn query_card_type(deps: Deps, config: &Config, wallet: &Addr) -> Result<CardType, ContractError> {
+   if config.staking_contract == "".to_string() {
+      return Err(ContractError::StakingContractNotSet {});
+   }
+   let user: StakingUserInfo = deps.querier.query_wasm_smart(
+      config.staking_contract.to_string(),
+      &StakingQueryMsg::GetUserInfo {
+         wallet: wallet.clone(),
+      },
+   )?;
+   Ok(user.card_type)
+}
+
+//---------lower registered tiers to current stake, drop wallets left without card---
+//---------goes on from the last rechecked wallet, true once all are rechecked------
+fn recheck_whitelist(
    deps: DepsMut,
+   config: &Config,
+   x: &mut ProjectState,
+   limit: usize,
+) -> Result<bool, ContractError> {
+   let start = WHITELIST_RECHECKED.may_load(deps.storage, x.project_id.u64())?;
+   let whitelist: StdResult<Vec<_>> = WHITELIST
+      .prefix(x.project_id.u64())
+      .range(
+         deps.storage,
+         start.as_ref().map(Bound::exclusive),
+         None,
+         Order::Ascending,
+      )
+      .take(limit + 1)
+      .collect();
+   let mut whitelist = whitelist?;
+   let done = whitelist.len() <= limit;
+   whitelist.truncate(limit);
+   if let Some((wallet, _)) = whitelist.last() {
+      WHITELIST_RECHECKED.save(deps.storage, x.project_id.u64(), wallet)?;
+   }
+   for (wallet, mut one) in whitelist {
+      let card_type = query_card_type(deps.as_ref(), config, &wallet)?;
+      let registered = card_weight(&x.tier_weights, &one.card_type);
+      let current = card_weight(&x.tier_weights, &card_type);
+      if current >= registered {
+         continue;
+      }
+      x.whitelist_weight -= registered - current;
+      if card_type == CardType::Other {
+         WHITELIST.remove(deps.storage, (x.project_id.u64(), &wallet));
+      } else {
+         one.card_type = card_type;
+         WHITELIST.save(deps.storage, (x.project_id.u64(), &wallet), &one)?;
+      }
+   }
+   Ok(done)
+}
+
+//---------anyone moves the recheck on before a large whitelist is closed-----------
+pub fn try_recheckwhitelist(
+   mut deps: DepsMut,
+   project_id: Uint64,
+   limit: Option<u32>,
+) -> Result<Response, ContractError> {
+   let mut x = projectstates().load(deps.storage, project_id.u64())?;
+   if x.project_status != ProjectStatus::Whitelist {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   let config = CONFIG.load(deps.storage)?;
+   let limit = limit.unwrap_or(RECHECK_LIMIT).min(RECHECK_LIMIT) as usize;
+   let done = recheck_whitelist(deps.branch(), &config, &mut x, limit)?;
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+
+   Ok(Response::new()
+      .add_attribute("action", "recheck whitelist")
+      .add_attribute("done", done.to_string()))
+}
+
+pub fn try_closewhitelist(
+   mut deps: DepsMut,
    env: Env,
    info: MessageInfo,
    project_id: Uint64,
 ) -> Result<Response, ContractError> {
-   let mut x = PROJECTSTATES.load(deps.storage, project_id.u64())?;
-   if info.sender != x.creator_wallet {
+   let mut x = projectstates().load(deps.storage, project_id.u64())?;
+   let lottery = LOTTERIES.may_load(deps.storage, project_id.u64())?;
+   //-----------anyone can close after whitelist period or reveal ended-------
+   let now = env.block.time.seconds();
+   let reveal_ended = match &lottery {
+      Some(lottery) => !lottery.reveal_end.is_zero() && now >= lottery.reveal_end.u64(),
+      None => false,
+   };
+   let expired = match &x.whitelist_period {
+      Some(period) => now >= period.end.u64(),
+      None => false,
+   };
+   if info.sender != x.creator_wallet && !expired && !reveal_ended {
       return Err(ContractError::Unauthorized {});
    }
    if x.project_status != ProjectStatus::Whitelist {
@@ -1080,74 +3381,204 @@ pub fn try_closewhitelist(
       });
    }
 
-   let backamount;
-   if x.backerbacked_amount >= x.project_collected {
-      backamount = Uint128::zero()
-   } else {
-      backamount = x.project_collected * Uint128::from(UST) - x.backerbacked_amount;
+   //-----------wallets unstaked after registering are downgraded------------
+   let config = CONFIG.load(deps.storage)?;
+   if !recheck_whitelist(deps.branch(), &config, &mut x, RECHECK_LIMIT as usize)? {
+      return Err(ContractError::RecheckPending {});
+   }
+   WHITELIST_RECHECKED.remove(deps.storage, project_id.u64());
+
+   //-----------in lottery mode only winners keep their entry----------------
+   let mut events: Vec<Event> = Vec::new();
+   if let Some(mut lottery) = lottery {
+      let revealed = lottery.creator_secret.is_some() && lottery.admin_secret.is_some();
+      //-----secrets are due at reveal end, or at whitelist end without both commits---
+      let due = match lottery.reveal_end.is_zero() {
+         true => expired,
+         false => reveal_ended,
+      };
+      if revealed {
+         draw_lottery(deps.storage, &mut x, &mut lottery)?;
+         LOTTERIES.save(deps.storage, project_id.u64(), &lottery)?;
+         let winners: Vec<String> = lottery.winners.iter().map(|x| x.to_string()).collect();
+         events.push(
+            Event::new(EVENT_LOTTERY_DRAWN)
+               .add_attribute(ATTR_PROJECT_ID, project_id.to_string())
+               .add_attribute(ATTR_SEED, lottery.seed)
+               .add_attribute(ATTR_WINNERS, winners.join(",")),
+         );
+      } else if !due {
+         return Err(ContractError::LotterySeedMissing {});
+      } else if lottery.admin_secret.is_some() {
+         //-----creator withheld its secret after seeing admin's, project is rejected---
+         let event = transition_project(&mut x, ProjectStatus::Rejected)?;
+         x.rejected_reason = String::from("lottery seed not revealed");
+         projectstates().save(deps.storage, project_id.u64(), &x)?;
+         return Ok(Response::new()
+            .add_event(event)
+            .add_attribute("action", "close whitelist"));
+      }
+      //-----admin withheld, or commits missing at whitelist end: everyone keeps the entry---
    }
-   let mut platium_count = 0;
-   let mut gold_count = 0;
-   let mut silver_count = 0;
-   let mut bronze_count = 0;
 
-   for one in x.whitelist.clone() {
-      match one.card_type {
-         CardType::Platium => {
-            platium_count += 1;
-         }
-         CardType::Gold => {
-            gold_count += 1;
-         }
-         CardType::Silver => {
-            silver_count += 1;
-         }
-         CardType::Bronze => {
-            bronze_count += 1;
-         }
-         other => {}
+   //-----------allocation is card weight times holder ticket----------------
+   //-----------community members join whitelist on first backing-----------
+   //-----------members added later share no ticket, so only these count----
+   let community = COMMUNITY.load(deps.storage)?;
+   WHITELIST_COMMUNITY.save(deps.storage, project_id.u64(), &community)?;
+   let tickets = calc_tickets(&x, config.decimals, community.len());
+   x.holder_ticket = tickets.holder_ticket;
+   x.community_ticket = tickets.community_ticket;
+
+   let event = transition_project(&mut x, ProjectStatus::Fundraising)?;
+
+   projectstates().save(deps.storage, project_id.u64(), &x)?;
+   Ok(Response::new()
+      .add_event(
+         Event::new(EVENT_ALLOCATION)
+            .add_attribute(ATTR_PROJECT_ID, project_id.to_string())
+            .add_attribute(ATTR_HOLDER_TICKET, x.holder_ticket.to_string())
+            .add_attribute(ATTR_COMMUNITY_TICKET, x.community_ticket.to_string()),
+      )
+      .add_events(events)
+      .add_event(event)
+      .add_attribute("action", "close whitelist"))
+}
+
+//---------creator and admin each commit to hex sha256 of a secret-------------
+pub fn try_commitlotteryseed(
+   deps: DepsMut,
+   env: Env,
+   info: MessageInfo,
+   project_id: Uint64,
+   commitment: String,
+) -> Result<Response, ContractError> {
+   let x = projectstates().load(deps.storage, project_id.u64())?;
+   if x.project_status != ProjectStatus::Whitelist {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   let mut lottery = LOTTERIES
+      .may_load(deps.storage, project_id.u64())?
+      .ok_or(ContractError::NoLottery {})?;
+   match hex::decode(&commitment) {
+      Ok(hash) if hash.len() == 32 => (),
+      _ => return Err(ContractError::InvalidCommitment {}),
+   }
+
+   let commit = match info.sender == x.creator_wallet {
+      true => &mut lottery.creator_commit,
+      false => {
+         check_role(deps.storage, &info.sender, Role::Admin)?;
+         &mut lottery.admin_commit
       }
+   };
+   if !commit.is_empty() {
+      return Err(ContractError::AlreadyCommitted {});
+   }
+   *commit = commitment.to_lowercase();
+   if !lottery.creator_commit.is_empty() && !lottery.admin_commit.is_empty() {
+      lottery.reveal_end = Uint64::new(env.block.time.seconds() + LOTTERY_REVEAL_PERIOD);
    }
+   LOTTERIES.save(deps.storage, project_id.u64(), &lottery)?;
 
-   x.holder_ticket = backamount * x.holder_alloc
-      / Uint128::from(100u128)
-      / Uint128::from(
-         (platium_count * 120 + gold_count * 50 + silver_count * 11 + bronze_count) as u128,
-      );
+   Ok(Response::new().add_attribute("action", "commit lottery seed"))
+}
 
-   let community = COMMUNITY.load(deps.storage)?;
-   x.community_ticket = backamount * (Uint128::from(100u128) - x.holder_alloc)
-      / Uint128::from(100u128)
-      / Uint128::from(community.len() as u128);
-
-   for i in 0..x.whitelist.len() {
-      let card_type = x.whitelist[i].card_type.clone();
-      match card_type {
-         CardType::Platium => {
-            x.whitelist[i].allocation = x.holder_ticket * Uint128::from(120u128);
-         }
-         CardType::Gold => {
-            x.whitelist[i].allocation = x.holder_ticket * Uint128::from(50u128);
-         }
-         CardType::Silver => {
-            x.whitelist[i].allocation = x.holder_ticket * Uint128::from(11u128);
-         }
-         CardType::Bronze => {
-            x.whitelist[i].allocation = x.holder_ticket;
-         }
-         other => {}
+//---------reveal after both committed, admin first so creator can't pick--------
+//---------first reveal closes registration------------------------------------
+pub fn try_reveallotteryseed(
+   deps: DepsMut,
+   info: MessageInfo,
+   project_id: Uint64,
+   secret: Binary,
+) -> Result<Response, ContractError> {
+   let x = projectstates().load(deps.storage, project_id.u64())?;
+   if x.project_status != ProjectStatus::Whitelist {
+      return Err(ContractError::NotCorrectStatus {
+         status: x.project_status as u32,
+      });
+   }
+   let mut lottery = LOTTERIES
+      .may_load(deps.storage, project_id.u64())?
+      .ok_or(ContractError::NoLottery {})?;
+   if lottery.creator_commit.is_empty() || lottery.admin_commit.is_empty() {
+      return Err(ContractError::LotterySeedMissing {});
+   }
+
+   if info.sender == x.creator_wallet && lottery.admin_secret.is_none() {
+      return Err(ContractError::AdminRevealFirst {});
+   }
+   let (commit, revealed) = match info.sender == x.creator_wallet {
+      true => (&lottery.creator_commit, &mut lottery.creator_secret),
+      false => {
+         check_role(deps.storage, &info.sender, Role::Admin)?;
+         (&lottery.admin_commit, &mut lottery.admin_secret)
       }
+   };
+   if hex::encode(Sha256::digest(secret.as_slice())) != *commit {
+      return Err(ContractError::InvalidCommitment {});
    }
-   for one in community {
-      x.whitelist.push(WhitelistState {
-         wallet: one,
-         card_type: CardType::Other,
-         allocation: x.community_ticket.clone(),
-         backed: Uint128::zero(),
+   *revealed = Some(secret);
+   LOTTERIES.save(deps.storage, project_id.u64(), &lottery)?;
+
+   Ok(Response::new().add_attribute("action", "reveal lottery seed"))
+}
+
+//---------wallets of each tier ranked by sha256(seed, wallet), losers dropped-----
+fn draw_lottery(
+   store: &mut dyn Storage,
+   x: &mut ProjectState,
+   lottery: &mut LotteryState,
+) -> Result<(), ContractError> {
+   let (creator_secret, admin_secret) = match (&lottery.creator_secret, &lottery.admin_secret) {
+      (Some(creator), Some(admin)) => (creator, admin),
+      _ => return Err(ContractError::LotterySeedMissing {}),
+   };
+   let seed = Sha256::new()
+      .chain(creator_secret.as_slice())
+      .chain(admin_secret.as_slice())
+      .finalize();
+
+   let whitelist: StdResult<Vec<_>> = WHITELIST
+      .prefix(x.project_id.u64())
+      .range(store, None, None, Order::Ascending)
+      .collect();
+   let mut ranked: Vec<(Vec<u8>, Addr, CardType)> = whitelist?
+      .into_iter()
+      .map(|(wallet, one)| {
+         let rank = Sha256::new()
+            .chain(&seed)
+            .chain(wallet.as_bytes())
+            .finalize()
+            .to_vec();
+         (rank, wallet, one.card_type)
       })
+      .collect();
+   ranked.sort_by(|a, b| a.0.cmp(&b.0));
+
+   let mut winners: Vec<Addr> = Vec::new();
+   let mut won = [0u32; 5];
+   for (_, wallet, card_type) in ranked {
+      let (tier, slots) = match card_type {
+         CardType::Platium => (0, lottery.slots.platium),
+         CardType::Gold => (1, lottery.slots.gold),
+         CardType::Silver => (2, lottery.slots.silver),
+         CardType::Bronze => (3, lottery.slots.bronze),
+         CardType::Other => (4, 0),
+      };
+      if won[tier] < slots {
+         won[tier] += 1;
+         winners.push(wallet);
+      } else {
+         x.whitelist_weight -= card_weight(&x.tier_weights, &card_type);
+         WHITELIST.remove(store, (x.project_id.u64(), &wallet));
+      }
    }
-   x.project_status = ProjectStatus::Fundraising;
+   winners.sort();
 
-   PROJECTSTATES.save(deps.storage, project_id.u64(), &x)?;
-   Ok(Response::new())
+   lottery.seed = hex::encode(seed);
+   lottery.winners = winners;
+   Ok(())
 }
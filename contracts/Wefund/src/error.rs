@@ -1,6 +1,7 @@
 use cosmwasm_std::StdError;
 use thiserror::Error;
-use cosmwasm_std::{Uint128};
+use cosmwasm_std::{Uint128, Uint64};
+use Interface::wefund::{ProjectStatus, Role};
 
 #[derive(Error, Debug)]
 pub enum ContractError {
@@ -62,6 +63,133 @@ pub enum ContractError {
     #[error("Not registered whitelist")]
     NotRegisteredWhitelist{ },
 
+    #[error("Allocation exceeded : allocation {allocation}, backed {backed}, amount {amount}")]
+    AllocationExceeded{
+        allocation: Uint128, backed: Uint128, amount: Uint128,
+    },
+
+    #[error("Invalid fee")]
+    InvalidFee{ },
+
+    #[error("Submission bond doesn't match config")]
+    InvalidBond{ },
+
+    #[error("Nothing to refund")]
+    NothingToRefund{ },
+
+    #[error("Not accepted denom : {denom}")]
+    NotAcceptedDenom{ denom: String },
+
+    #[error("Invalid soft target")]
+    InvalidSoftcap{ },
+
+    #[error("Invalid period")]
+    InvalidPeriod{ },
+
+    #[error("Out of period : {start} - {end}")]
+    OutOfPeriod{ start: Uint64, end: Uint64 },
+
+    #[error("Fundraising not ended")]
+    FundraisingNotEnded{ },
+
+    #[error("Invalid voting config")]
+    InvalidVotingConfig{ },
+
+    #[error("Voting ended")]
+    VotingEnded{ },
+
+    #[error("Voting not ended")]
+    VotingNotEnded{ },
+
+    #[error("Already proposed for approval")]
+    AlreadyProposed{ },
+
+    #[error("Invalid relayer key")]
+    InvalidRelayerKey{ },
+
+    #[error("Invalid signature")]
+    InvalidSignature{ },
+
+    #[error("Attestation already used")]
+    AttestationUsed{ },
+
+    #[error("Attestation is for another chain or contract")]
+    AttestationTarget{ },
+
+    #[error("Backing exceeds remaining target : remaining {remaining}, amount {amount}")]
+    TargetExceeded{ remaining: Uint128, amount: Uint128 },
+
+    #[error("Merkle root must be 32 bytes")]
+    InvalidMerkleRoot{ },
+
+    #[error("Project has no merkle whitelist")]
+    NoMerkleRoot{ },
+
+    #[error("Invalid merkle proof")]
+    InvalidMerkleProof{ },
+
+    #[error("Public cap exceeded : cap {cap}, backed {backed}, amount {amount}")]
+    PublicCapExceeded{
+        cap: Uint128, backed: Uint128, amount: Uint128,
+    },
+
+    #[error("Project has no public sale")]
+    NoPublicSale{ },
+
+    #[error("Guaranteed allocation window has not ended")]
+    GuaranteedNotEnded{ },
+
+    #[error("Project has no lottery")]
+    NoLottery{ },
+
+    #[error("Lottery seed already committed")]
+    AlreadyCommitted{ },
+
+    #[error("Secret does not match commitment")]
+    InvalidCommitment{ },
+
+    #[error("Lottery seed needs commit and reveal of creator and admin")]
+    LotterySeedMissing{ },
+
+    #[error("Admin reveals lottery seed first")]
+    AdminRevealFirst{ },
+
+    #[error("Whitelist registration is closed")]
+    RegistrationClosed{ },
+
+    #[error("Tier weights and lottery can't change once wallets registered")]
+    WhitelistRegistered{ },
+
+    #[error("Whitelist recheck is pending, send RecheckWhitelist first")]
+    RecheckPending{ },
+
+    #[error("Staking contract is not set")]
+    StakingContractNotSet{ },
+
+    #[error("No staked card tier")]
+    NoCardTier{ },
+
+    #[error("Holder allocation is a percent, at most 100")]
+    InvalidHolderAlloc{ },
+
+    #[error("Invalid position token id : {token_id}")]
+    InvalidTokenId{ token_id: String },
+
+    #[error("Approval already expired")]
+    Expired{ },
+
+    #[error("Missing role: {:?}", role)]
+    MissingRole{ role: Role },
+
+    #[error("Not allowed status transition: {:?} to {:?}", from, to)]
+    NotAllowedTransition{ from: ProjectStatus, to: ProjectStatus },
+
+    #[error("Cannot migrate from contract {name}")]
+    InvalidContractName{ name: String },
+
+    #[error("Cannot downgrade from version {version}")]
+    CannotDowngrade{ version: String },
+
     #[error("Not correct Milestone status : {aust_balance} {estimate_exchange_rate} {epoch_exchange_rate} {withdraw_amount} {release_amount}")]
     Testing{
         aust_balance: String,
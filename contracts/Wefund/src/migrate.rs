@@ -0,0 +1,297 @@
+use cosmwasm_std::{Addr, Decimal, DepsMut, Env, Order, StdResult, Uint128, Uint64};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::contract::{
+    add_coin, card_weight, claim_vote_weight, default_tier_weights, open_milestone_vote,
+    take_escrow, tally_vote,
+};
+use crate::error::ContractError;
+use crate::state::{
+    projectstates, save_position, BACKERS, COMMUNITY, CONFIG, MILESTONES, VOTES, WHITELIST,
+    WHITELIST_COMMUNITY,
+};
+use Interface::staking::CardType;
+use Interface::wefund::{
+    BackerState, Config, Milestone, Position, ProjectState, ProjectStatus, TeamMember,
+    VestingParameter, Vote, VoteOption, WhitelistState,
+};
+
+//------------layouts stored before 18.0.0---------------------------------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub owner: Addr,
+    pub wefund: Addr,
+    pub denom: String,
+    pub decimals: u32,
+    pub vesting_contract: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyVote {
+    pub wallet: Addr,
+    pub voted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyMilestone {
+    pub milestone_step: Uint128,
+    pub milestone_name: String,
+    pub milestone_type: String,
+    pub milestone_description: String,
+    pub milestone_startdate: String,
+    pub milestone_enddate: String,
+    pub milestone_amount: Uint128,
+    pub milestone_status: Uint128,
+    pub milestone_votes: Vec<LegacyVote>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyProjectState {
+    pub project_company: String,
+    pub project_title: String,
+    pub project_description: String,
+    pub project_ecosystem: String,
+    pub project_fundtype: String,
+    pub project_createddate: String,
+    pub project_saft: String,
+    pub project_logo: String,
+    pub project_whitepaper: String,
+    pub project_website: String,
+    pub project_email: String,
+
+    pub country: String,
+    pub cofounder_name: String,
+    pub service_wefund: String,
+    pub service_charity: String,
+    pub professional_link: String,
+
+    pub project_id: Uint64,
+    pub creator_wallet: Addr,
+    pub project_collected: Uint128,
+    pub project_status: ProjectStatus,
+    pub fundraising_stage: Uint128,
+    pub backerbacked_amount: Uint128,
+    pub backer_states: Vec<BackerState>,
+    pub milestone_states: Vec<LegacyMilestone>,
+    pub project_milestonestep: Uint128,
+    pub teammember_states: Vec<TeamMember>,
+    pub vesting: Vec<VestingParameter>,
+    pub token_addr: Addr,
+    pub whitelist: Vec<WhitelistState>,
+    pub holder_alloc: Uint128,
+    pub holder_ticket: Uint128,
+    pub community_ticket: Uint128,
+}
+
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+pub const LEGACY_PROJECTSTATES: Map<u64, LegacyProjectState> = Map::new("prj");
+
+//------------add fee settings to config-----------------------------------
+pub fn migrate_config_v18(deps: DepsMut) -> Result<(), ContractError> {
+    let x = LEGACY_CONFIG.load(deps.storage)?;
+    let config = Config {
+        owner: x.owner,
+        wefund: x.wefund,
+        denom: x.denom,
+        decimals: x.decimals,
+        vesting_contract: x.vesting_contract,
+        staking_contract: Addr::unchecked(""),
+        fee_bps: 500, //5% as before
+        fee_recipients: Vec::new(),
+        bond_amount: Uint128::zero(),
+        bond_token: Addr::unchecked(""),
+        bond_token_amount: Uint128::zero(),
+        bond_slash_bps: 0,
+        approval_quorum: Decimal::percent(50),
+        approval_threshold: Decimal::percent(50),
+        approval_vote_period: Uint64::new(604800), //one week
+    };
+    CONFIG.save(deps.storage, &config)?;
+    Ok(())
+}
+
+//------------split monolithic projects into project, backer, whitelist and milestone maps----
+pub fn migrate_projects_v18(deps: DepsMut, env: &Env) -> Result<(), ContractError> {
+    let legacy: StdResult<Vec<_>> = LEGACY_PROJECTSTATES
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect();
+
+    for (id, old) in legacy? {
+        //-----raw entry goes first, index update would read it as new layout---
+        LEGACY_PROJECTSTATES.remove(deps.storage, id);
+        //-----legacy backed amount is what is left in escrow after releases-----
+        let escrow_left = old.backerbacked_amount;
+
+        let mut x = ProjectState {
+            project_company: old.project_company,
+            project_title: old.project_title,
+            project_description: old.project_description,
+            project_ecosystem: old.project_ecosystem,
+            project_fundtype: old.project_fundtype,
+            project_createddate: old.project_createddate,
+            project_saft: old.project_saft,
+            project_logo: old.project_logo,
+            project_whitepaper: old.project_whitepaper,
+            project_website: old.project_website,
+            project_email: old.project_email,
+
+            country: old.country,
+            cofounder_name: old.cofounder_name,
+            service_wefund: old.service_wefund,
+            service_charity: old.service_charity,
+            professional_link: old.professional_link,
+
+            project_id: old.project_id,
+            revision: Uint64::zero(),
+            previous_hash: String::new(),
+            creator_wallet: old.creator_wallet,
+            project_collected: old.project_collected,
+            project_softcap: old.project_collected,
+
+            project_status: old.project_status,
+            fundraising_stage: old.fundraising_stage,
+            fee_bps: None,
+
+            approval_vote_end: Uint64::zero(),
+            approval_total: Uint64::zero(),
+            approval_yes: Uint64::zero(),
+            approval_no: Uint64::zero(),
+            approval_abstain: Uint64::zero(),
+            rejected_reason: String::new(),
+
+            backerbacked_amount: Uint128::zero(),
+            backerbacked_funds: Vec::new(),
+            backerbacked_total_funds: Vec::new(),
+            crosschain_backed_amount: Uint128::zero(),
+            backer_count: Uint64::zero(),
+
+            milestone_count: Uint64::new(old.milestone_states.len() as u64),
+            project_milestonestep: old.project_milestonestep,
+            milestone_quorum: Decimal::percent(50),
+            milestone_threshold: Decimal::percent(50),
+            milestone_vote_period: Uint64::new(604800), //one week
+            milestone_vote_end: Uint64::zero(),
+            milestone_max_rejections: Uint64::new(3),
+            milestone_rejections: Uint64::zero(),
+            milestone_vote_round: Uint64::zero(),
+            milestone_total_weight: Uint128::zero(),
+            milestone_yes: Uint128::zero(),
+            milestone_no: Uint128::zero(),
+            milestone_abstain: Uint128::zero(),
+
+            teammember_states: old.teammember_states,
+            vesting: old.vesting,
+
+            token_addr: old.token_addr,
+            whitelist_weight: Uint128::zero(),
+            tier_weights: default_tier_weights(),
+            holder_alloc: old.holder_alloc,
+            holder_ticket: old.holder_ticket,
+            community_ticket: old.community_ticket,
+            whitelist_period: None,
+            fundraising_period: None,
+            public_sale: None,
+            public_start: Uint64::zero(),
+        };
+
+        for one in old.whitelist.iter() {
+            if one.card_type != CardType::Other {
+                x.whitelist_weight += card_weight(&x.tier_weights, &one.card_type);
+            }
+            WHITELIST.save(deps.storage, (id, &one.wallet), one)?;
+        }
+
+        //-----backings grouped by wallet, escrow starts from all backed funds-----
+        for backer in old.backer_states {
+            let key = (id, &backer.backer_wallet);
+            let mut backings = BACKERS.may_load(deps.storage, key)?.unwrap_or_default();
+            if backings.is_empty() {
+                x.backer_count += Uint64::new(1);
+            }
+            x.backerbacked_amount += backer.amount.amount;
+            add_coin(&mut x.backerbacked_funds, &backer.amount);
+            add_coin(&mut x.backerbacked_total_funds, &backer.amount);
+
+            //-----one position per backing, weighted by its backed amount-----
+            //-----legacy tokens stay vested per wallet, token_amount is left 0--
+            x.milestone_total_weight += backer.amount.amount;
+            let mut position = Position {
+                token_id: Uint64::zero(),
+                owner: backer.backer_wallet.clone(),
+                project_id: x.project_id,
+                stage: Uint128::zero(),
+                amount: backer.amount.clone(),
+                backed: backer.amount.amount,
+                token_amount: Uint128::zero(),
+                weight: backer.amount.amount,
+                voted_round: Uint64::zero(),
+                refunded: false,
+                crosschain: false,
+                approvals: Vec::new(),
+            };
+            save_position(deps.storage, &mut position)?;
+
+            backings.push(backer.clone());
+            BACKERS.save(deps.storage, key, &backings)?;
+        }
+
+        //-----released milestones and completed projects already left the escrow---
+        let released = x.backerbacked_amount.saturating_sub(escrow_left);
+        take_escrow(&mut x, released);
+
+        let mut yes_votes = Vec::new();
+        for (step, milestone) in old.milestone_states.into_iter().enumerate() {
+            if step as u128 == x.project_milestonestep.u128() {
+                yes_votes = milestone
+                    .milestone_votes
+                    .iter()
+                    .filter(|vote| vote.voted)
+                    .map(|vote| vote.wallet.clone())
+                    .collect();
+            }
+            let new_milestone = Milestone {
+                milestone_step: milestone.milestone_step,
+                milestone_name: milestone.milestone_name,
+                milestone_type: milestone.milestone_type,
+                milestone_description: milestone.milestone_description,
+                milestone_startdate: milestone.milestone_startdate,
+                milestone_enddate: milestone.milestone_enddate,
+                milestone_amount: milestone.milestone_amount,
+                milestone_status: milestone.milestone_status,
+            };
+            MILESTONES.save(deps.storage, (id, step as u64), &new_milestone)?;
+        }
+
+        //-----running vote restarts, yes votes of holders are kept-------------
+        if x.project_status == ProjectStatus::Releasing {
+            open_milestone_vote(&mut x, env);
+            for wallet in yes_votes {
+                let weight = claim_vote_weight(deps.storage, &x, &wallet)?;
+                if weight.is_zero() {
+                    continue;
+                }
+                let vote = Vote {
+                    wallet: wallet.clone(),
+                    weight,
+                    option: VoteOption::Yes,
+                    round: x.milestone_vote_round,
+                };
+                tally_vote(&mut x, &vote, true);
+                VOTES.save(deps.storage, (id, &wallet), &vote)?;
+            }
+        }
+
+        //-----legacy tickets were split over the community of today----------
+        if x.project_status != ProjectStatus::WefundVote
+            && x.project_status != ProjectStatus::Whitelist
+        {
+            let community = COMMUNITY.may_load(deps.storage)?.unwrap_or_default();
+            WHITELIST_COMMUNITY.save(deps.storage, id, &community)?;
+        }
+
+        projectstates().save(deps.storage, id, &x)?;
+    }
+    Ok(())
+}
@@ -1,12 +1,12 @@
 use serde::de::DeserializeOwned;
 
-
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-   from_binary, from_slice, to_binary, Coin, ContractResult, CustomQuery, Empty,
-   OwnedDeps, Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+   from_binary, from_slice, to_binary, Coin, ContractResult, CustomQuery, Empty, OwnedDeps,
+   Querier, QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
-use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg};
+use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
+use Interface::staking::{CardType, QueryMsg as StakingQueryMsg, UserInfo as StakingUserInfo};
 
 use std::collections::HashMap;
 use std::marker::PhantomData;
@@ -28,6 +28,7 @@ pub fn mock_dependencies(
 pub struct WasmMockQuerier<C: DeserializeOwned = Empty> {
    base: MockQuerier<C>,
    token_querier: TokenQuerier,
+   card_types: HashMap<String, CardType>,
 }
 
 #[derive(Clone, Default)]
@@ -79,6 +80,25 @@ impl<C: CustomQuery + DeserializeOwned> WasmMockQuerier<C> {
    pub fn handle_query(&self, request: &QueryRequest<C>) -> QuerierResult {
       match &request {
          QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+            if let Ok(StakingQueryMsg::GetUserInfo { wallet }) = from_binary(msg) {
+               let card_type = self
+                  .card_types
+                  .get(wallet.as_str())
+                  .cloned()
+                  .unwrap_or(CardType::Other);
+               return SystemResult::Ok(ContractResult::Ok(
+                  to_binary(&StakingUserInfo {
+                     wallet,
+                     amount: Uint128::zero(),
+                     last_withdraw_time: Uint128::zero(),
+                     reward_amount: Uint128::zero(),
+                     last_reward_time: Uint128::zero(),
+                     card_type,
+                     card_number: Uint128::zero(),
+                  })
+                  .unwrap(),
+               ));
+            }
             match from_binary(msg).unwrap() {
                Cw20QueryMsg::Balance { address } => {
                   let balances: &HashMap<String, Uint128> =
@@ -111,6 +131,15 @@ impl<C: CustomQuery + DeserializeOwned> WasmMockQuerier<C> {
                      to_binary(&Cw20BalanceResponse { balance }).unwrap(),
                   ))
                }
+               Cw20QueryMsg::TokenInfo {} => SystemResult::Ok(ContractResult::Ok(
+                  to_binary(&TokenInfoResponse {
+                     name: contract_addr.to_string(),
+                     symbol: "TOKEN".to_string(),
+                     decimals: 6,
+                     total_supply: Uint128::zero(),
+                  })
+                  .unwrap(),
+               )),
                _ => panic!("DO NOT ENTER HERE"),
             }
          }
@@ -124,6 +153,7 @@ impl WasmMockQuerier {
       WasmMockQuerier {
          base,
          token_querier: TokenQuerier::default(),
+         card_types: HashMap::new(),
       }
    }
 
@@ -131,4 +161,9 @@ impl WasmMockQuerier {
    pub fn with_token_balances(&mut self, balances: &[(&String, &[(&String, &Uint128)])]) {
       self.token_querier = TokenQuerier::new(balances);
    }
-}
\ No newline at end of file
+
+   // card tier staked by wallet, Other if not set
+   pub fn with_card_type(&mut self, wallet: &str, card_type: CardType) {
+      self.card_types.insert(wallet.to_string(), card_type);
+   }
+}
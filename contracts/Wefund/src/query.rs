@@ -1,13 +1,38 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, AllBalanceResponse, BankQuery, Binary, Coin, Deps, Env, QueryRequest,
-    StdResult, Uint128, Uint64,
+    to_binary, Addr, AllBalanceResponse, BankQuery, Binary, Coin, Deps, Env, Order, QueryRequest,
+    StdError, StdResult, Uint128, Uint64,
 };
 use cw20::{BalanceResponse as Cw20BalanceResponse, Cw20QueryMsg};
+use cw_storage_plus::Bound;
 
-use crate::state::{COMMUNITY, CONFIG, PROJECTSTATES};
-use Interface::wefund::{BackerState, Config, ProjectState, QueryMsg};
+use crate::contract::{
+    calc_allocation, calc_approval_tally, calc_milestone_tally, calc_refundable, calc_tickets,
+    load_allocation,
+};
+use crate::state::{
+    positions, projectstates, status_key, ACCEPTED_DENOMS, ATTESTATIONS, BACKERS, BONDS, COMMUNITY,
+    CONFIG, LOTTERIES, MERKLE_ROOTS, MILESTONES, POSITION_SEQ, PUBLIC_BACKED, RELAYER_KEYS, ROLES,
+    VOTES, WHITELIST,
+};
+use Interface::cw721::{
+    AllNftInfoResponse, ContractInfoResponse, NftInfoResponse, NumTokensResponse, OwnerOfResponse,
+    TokensResponse,
+};
+use Interface::wefund::{
+    AcceptedDenom, AllocationPreviewResponse, AllocationResponse, ApprovalTallyResponse,
+    BackerState, BondState, Config, LotteryState, Milestone, MilestoneTallyResponse, Position,
+    ProjectFilter, ProjectState, ProjectStatus, ProjectSummary, QueryMsg, RoleInfo, Vote,
+    WhitelistState,
+};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+//------------cw721 contract info of backer positions-------------------------
+const POSITION_NAME: &str = "WeFund Backer Position";
+const POSITION_SYMBOL: &str = "WFDPOS";
 
 // version info for migration info
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -15,10 +40,112 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetBalance { wallet } => to_binary(&query_balance(deps, _env, wallet)?),
         QueryMsg::GetConfig {} => to_binary(&query_getconfig(deps)?),
-        QueryMsg::GetAllProject {} => to_binary(&query_allproject(deps)?),
+        QueryMsg::GetAllProject {
+            start_after,
+            limit,
+            filter,
+        } => to_binary(&query_allproject(deps, start_after, limit, filter)?),
+        QueryMsg::GetProjectSummaries {
+            start_after,
+            limit,
+            filter,
+        } => to_binary(&query_projectsummaries(deps, start_after, limit, filter)?),
         QueryMsg::GetProject { project_id } => to_binary(&query_project(deps, project_id)?),
-        QueryMsg::GetBacker { project_id } => to_binary(&query_backer(deps, project_id)?),
+        QueryMsg::GetBacker {
+            project_id,
+            start_after,
+            limit,
+        } => to_binary(&query_backer(deps, project_id, start_after, limit)?),
+        QueryMsg::GetWhitelist {
+            project_id,
+            start_after,
+            limit,
+        } => to_binary(&query_whitelist(deps, project_id, start_after, limit)?),
+        QueryMsg::GetMilestones { project_id } => to_binary(&query_milestones(deps, project_id)?),
+        QueryMsg::GetVote { project_id, wallet } => {
+            to_binary(&query_vote(deps, project_id, wallet)?)
+        }
         QueryMsg::GetCommunitymembers {} => to_binary(&query_communitymembers(deps)?),
+        QueryMsg::GetAcceptedDenoms {} => to_binary(&query_accepteddenoms(deps)?),
+        QueryMsg::GetRefundable { project_id, wallet } => {
+            to_binary(&query_refundable(deps, project_id, wallet)?)
+        }
+        QueryMsg::GetAllocation { project_id, wallet } => {
+            to_binary(&query_allocation(deps, project_id, wallet)?)
+        }
+        QueryMsg::GetMilestoneTally { project_id } => {
+            to_binary(&query_milestonetally(deps, project_id)?)
+        }
+        QueryMsg::GetRoles { start_after, limit } => {
+            to_binary(&query_roles(deps, start_after, limit)?)
+        }
+        QueryMsg::GetBond { project_id } => to_binary(&query_bond(deps, project_id)?),
+        QueryMsg::GetApprovalTally { project_id } => {
+            to_binary(&query_approvaltally(deps, project_id)?)
+        }
+        QueryMsg::GetRelayerKeys {} => to_binary(&query_relayerkeys(deps)?),
+        QueryMsg::GetAttestation {
+            otherchain,
+            source_tx_hash,
+        } => to_binary(&query_attestation(deps, otherchain, source_tx_hash)?),
+        QueryMsg::GetLottery { project_id } => to_binary(&query_lottery(deps, project_id)?),
+        QueryMsg::GetMerkleRoot { project_id } => to_binary(&query_merkleroot(deps, project_id)?),
+        QueryMsg::PreviewAllocation { project_id } => {
+            to_binary(&query_previewallocation(deps, project_id)?)
+        }
+        QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        } => to_binary(&query_ownerof(
+            deps,
+            _env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::NftInfo { token_id } => to_binary(&query_nftinfo(deps, token_id)?),
+        QueryMsg::AllNftInfo {
+            token_id,
+            include_expired,
+        } => to_binary(&AllNftInfoResponse {
+            access: query_ownerof(
+                deps,
+                _env,
+                token_id.clone(),
+                include_expired.unwrap_or(false),
+            )?,
+            info: query_nftinfo(deps, token_id)?,
+        }),
+        QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_tokens(deps, owner, start_after, limit)?),
+        QueryMsg::AllTokens { start_after, limit } => {
+            to_binary(&query_alltokens(deps, start_after, limit)?)
+        }
+        QueryMsg::NumTokens {} => to_binary(&NumTokensResponse {
+            count: POSITION_SEQ
+                .may_load(deps.storage)?
+                .unwrap_or_default()
+                .u64(),
+        }),
+        QueryMsg::ContractInfo {} => to_binary(&ContractInfoResponse {
+            name: POSITION_NAME.to_string(),
+            symbol: POSITION_SYMBOL.to_string(),
+        }),
+        QueryMsg::GetPosition { token_id } => to_binary(&query_position(deps, token_id)?),
+        QueryMsg::GetPositions {
+            owner,
+            project_id,
+            start_after,
+            limit,
+        } => to_binary(&query_positions(
+            deps,
+            owner,
+            project_id,
+            start_after,
+            limit,
+        )?),
     }
 }
 
@@ -26,6 +153,17 @@ fn query_communitymembers(deps: Deps) -> StdResult<Vec<Addr>> {
     let community = COMMUNITY.load(deps.storage).unwrap();
     Ok(community)
 }
+fn query_accepteddenoms(deps: Deps) -> StdResult<Vec<AcceptedDenom>> {
+    let all: StdResult<Vec<_>> = ACCEPTED_DENOMS
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .collect();
+
+    let mut accepted: Vec<AcceptedDenom> = Vec::new();
+    for x in all? {
+        accepted.push(x.1);
+    }
+    Ok(accepted)
+}
 fn query_balance(deps: Deps, _env: Env, wallet: String) -> StdResult<AllBalanceResponse> {
     // let uusd_denom = String::from("uusd");
     let mut balance: AllBalanceResponse =
@@ -40,23 +178,359 @@ fn query_getconfig(deps: Deps) -> StdResult<Config> {
     let config = CONFIG.load(deps.storage).unwrap();
     Ok(config)
 }
-fn query_allproject(deps: Deps) -> StdResult<Vec<ProjectState>> {
-    let all: StdResult<Vec<_>> = PROJECTSTATES
-        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
-        .collect();
-    let all = all.unwrap();
+fn query_allproject(
+    deps: Deps,
+    start_after: Option<Uint64>,
+    limit: Option<u32>,
+    filter: Option<ProjectFilter>,
+) -> StdResult<Vec<ProjectState>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|x| Bound::exclusive(x.u64()));
+    let filter = filter.unwrap_or_default();
+
+    //--------use index for the first filter set, match the rest----------
+    let projects = projectstates();
+    let iter = if let Some(status) = &filter.project_status {
+        projects.idx.status.prefix(status_key(status)).range(
+            deps.storage,
+            start,
+            None,
+            cosmwasm_std::Order::Ascending,
+        )
+    } else if let Some(creator_wallet) = &filter.creator_wallet {
+        let creator_wallet = deps.api.addr_validate(creator_wallet)?;
+        projects.idx.creator.prefix(creator_wallet).range(
+            deps.storage,
+            start,
+            None,
+            cosmwasm_std::Order::Ascending,
+        )
+    } else if let Some(ecosystem) = &filter.project_ecosystem {
+        projects.idx.ecosystem.prefix(ecosystem.clone()).range(
+            deps.storage,
+            start,
+            None,
+            cosmwasm_std::Order::Ascending,
+        )
+    } else if let Some(fundtype) = &filter.project_fundtype {
+        projects.idx.fundtype.prefix(fundtype.clone()).range(
+            deps.storage,
+            start,
+            None,
+            cosmwasm_std::Order::Ascending,
+        )
+    } else {
+        projects.range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+    };
 
     let mut all_project: Vec<ProjectState> = Vec::new();
-    for x in all {
-        all_project.push(x.1);
+    for item in iter {
+        let (_, x) = item?;
+        if !match_filter(&x, &filter) {
+            continue;
+        }
+        all_project.push(x);
+        if all_project.len() >= limit {
+            break;
+        }
     }
     Ok(all_project)
 }
-fn query_backer(deps: Deps, id: Uint64) -> StdResult<Vec<BackerState>> {
-    let x = PROJECTSTATES.load(deps.storage, id.u64())?;
-    Ok(x.backer_states)
+fn query_projectsummaries(
+    deps: Deps,
+    start_after: Option<Uint64>,
+    limit: Option<u32>,
+    filter: Option<ProjectFilter>,
+) -> StdResult<Vec<ProjectSummary>> {
+    let all_project = query_allproject(deps, start_after, limit, filter)?;
+    Ok(all_project
+        .into_iter()
+        .map(|x| ProjectSummary {
+            project_id: x.project_id,
+            project_company: x.project_company,
+            project_title: x.project_title,
+            project_ecosystem: x.project_ecosystem,
+            project_fundtype: x.project_fundtype,
+            project_logo: x.project_logo,
+            creator_wallet: x.creator_wallet,
+            project_collected: x.project_collected,
+            project_softcap: x.project_softcap,
+            project_status: x.project_status,
+            backerbacked_amount: x.backerbacked_amount,
+            backer_count: x.backer_count,
+            milestone_count: x.milestone_count,
+            project_milestonestep: x.project_milestonestep,
+            fundraising_period: x.fundraising_period,
+        })
+        .collect())
+}
+fn match_filter(x: &ProjectState, filter: &ProjectFilter) -> bool {
+    if let Some(status) = &filter.project_status {
+        if &x.project_status != status {
+            return false;
+        }
+    }
+    if let Some(creator_wallet) = &filter.creator_wallet {
+        if x.creator_wallet.as_str() != creator_wallet {
+            return false;
+        }
+    }
+    if let Some(ecosystem) = &filter.project_ecosystem {
+        if &x.project_ecosystem != ecosystem {
+            return false;
+        }
+    }
+    if let Some(fundtype) = &filter.project_fundtype {
+        if &x.project_fundtype != fundtype {
+            return false;
+        }
+    }
+    true
+}
+fn query_backer(
+    deps: Deps,
+    id: Uint64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<BackerState>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|x| deps.api.addr_validate(&x))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    //--------limit counts wallets, all backings of a wallet are returned--------
+    let mut backers: Vec<BackerState> = Vec::new();
+    for item in BACKERS
+        .prefix(id.u64())
+        .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+        .take(limit)
+    {
+        let (_, backings) = item?;
+        backers.extend(backings);
+    }
+    Ok(backers)
+}
+fn query_whitelist(
+    deps: Deps,
+    id: Uint64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<WhitelistState>> {
+    let x = projectstates().load(deps.storage, id.u64())?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|x| deps.api.addr_validate(&x))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    WHITELIST
+        .prefix(id.u64())
+        .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, mut one) = item?;
+            one.allocation = calc_allocation(&x, &one.card_type);
+            Ok(one)
+        })
+        .collect()
+}
+fn query_milestones(deps: Deps, id: Uint64) -> StdResult<Vec<Milestone>> {
+    MILESTONES
+        .prefix(id.u64())
+        .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+        .map(|item| item.map(|(_, milestone)| milestone))
+        .collect()
+}
+fn query_vote(deps: Deps, id: Uint64, wallet: String) -> StdResult<Option<Vote>> {
+    let wallet = deps.api.addr_validate(&wallet)?;
+    VOTES.may_load(deps.storage, (id.u64(), &wallet))
 }
 fn query_project(deps: Deps, id: Uint64) -> StdResult<ProjectState> {
-    let x = PROJECTSTATES.load(deps.storage, id.u64())?;
+    let x = projectstates().load(deps.storage, id.u64())?;
     Ok(x)
 }
+fn query_refundable(deps: Deps, id: Uint64, wallet: String) -> StdResult<Vec<Coin>> {
+    let x = projectstates().load(deps.storage, id.u64())?;
+    if x.project_status != ProjectStatus::Fail {
+        return Ok(Vec::new());
+    }
+    let wallet = deps.api.addr_validate(&wallet)?;
+    calc_refundable(deps.storage, &x, &wallet)
+}
+fn query_allocation(deps: Deps, id: Uint64, wallet: String) -> StdResult<AllocationResponse> {
+    let x = projectstates().load(deps.storage, id.u64())?;
+    let wallet = deps.api.addr_validate(&wallet)?;
+
+    let mut allocation = AllocationResponse {
+        allocation: Uint128::zero(),
+        backed: Uint128::zero(),
+        remaining: Uint128::zero(),
+    };
+    //------in public sale every wallet has the same cap------
+    if let (ProjectStatus::PublicSale, Some(public_sale)) = (&x.project_status, &x.public_sale) {
+        allocation.allocation = public_sale.wallet_cap;
+        allocation.backed = PUBLIC_BACKED
+            .may_load(deps.storage, (id.u64(), &wallet))?
+            .unwrap_or_default();
+        allocation.remaining = allocation.allocation.saturating_sub(allocation.backed);
+    } else if let Some(one) = load_allocation(deps.storage, &x, &wallet)? {
+        allocation.allocation = one.allocation;
+        allocation.backed = one.backed;
+        allocation.remaining = one.allocation.saturating_sub(one.backed);
+    }
+    Ok(allocation)
+}
+fn query_milestonetally(deps: Deps, id: Uint64) -> StdResult<MilestoneTallyResponse> {
+    let x = projectstates().load(deps.storage, id.u64())?;
+    Ok(calc_milestone_tally(&x))
+}
+fn query_roles(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<RoleInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|x| deps.api.addr_validate(&x))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    ROLES
+        .range(deps.storage, start, None, cosmwasm_std::Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (wallet, roles) = item?;
+            Ok(RoleInfo { wallet, roles })
+        })
+        .collect()
+}
+fn query_bond(deps: Deps, id: Uint64) -> StdResult<Option<BondState>> {
+    BONDS.may_load(deps.storage, id.u64())
+}
+fn query_approvaltally(deps: Deps, id: Uint64) -> StdResult<ApprovalTallyResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let x = projectstates().load(deps.storage, id.u64())?;
+    Ok(calc_approval_tally(&config, &x))
+}
+fn query_position(deps: Deps, token_id: Uint64) -> StdResult<Position> {
+    positions().load(deps.storage, token_id.u64())
+}
+fn query_positions(
+    deps: Deps,
+    owner: String,
+    project_id: Option<Uint64>,
+    start_after: Option<Uint64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Position>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let owner = deps.api.addr_validate(&owner)?;
+    let start = start_after.map(|x| Bound::exclusive(x.u64()));
+
+    let mut held: Vec<Position> = Vec::new();
+    for item in positions().idx.owner.prefix(owner).range(
+        deps.storage,
+        start,
+        None,
+        cosmwasm_std::Order::Ascending,
+    ) {
+        let (_, x) = item?;
+        if matches!(project_id, Some(id) if id != x.project_id) {
+            continue;
+        }
+        held.push(x);
+        if held.len() >= limit {
+            break;
+        }
+    }
+    Ok(held)
+}
+fn parse_token_id(token_id: &str) -> StdResult<u64> {
+    token_id
+        .parse::<u64>()
+        .map_err(|_| StdError::generic_err(format!("Invalid token id : {}", token_id)))
+}
+fn query_ownerof(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<OwnerOfResponse> {
+    let x = positions().load(deps.storage, parse_token_id(&token_id)?)?;
+    Ok(OwnerOfResponse {
+        owner: x.owner.to_string(),
+        approvals: x
+            .approvals
+            .into_iter()
+            .filter(|a| include_expired || !a.expires.is_expired(&env.block))
+            .collect(),
+    })
+}
+fn query_nftinfo(deps: Deps, token_id: String) -> StdResult<NftInfoResponse<Position>> {
+    let x = positions().load(deps.storage, parse_token_id(&token_id)?)?;
+    Ok(NftInfoResponse {
+        token_uri: None,
+        extension: x,
+    })
+}
+fn query_tokens(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let owner = deps.api.addr_validate(&owner)?;
+    let start = match start_after {
+        Some(x) => Some(Bound::exclusive(parse_token_id(&x)?)),
+        None => None,
+    };
+    let tokens: StdResult<Vec<_>> = positions()
+        .idx
+        .owner
+        .prefix(owner)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|x| x.map(|id| id.to_string()))
+        .collect();
+    Ok(TokensResponse { tokens: tokens? })
+}
+fn query_alltokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = match start_after {
+        Some(x) => Some(Bound::exclusive(parse_token_id(&x)?)),
+        None => None,
+    };
+    let tokens: StdResult<Vec<_>> = positions()
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|x| x.map(|id| id.to_string()))
+        .collect();
+    Ok(TokensResponse { tokens: tokens? })
+}
+fn query_relayerkeys(deps: Deps) -> StdResult<Vec<Binary>> {
+    Ok(RELAYER_KEYS.may_load(deps.storage)?.unwrap_or_default())
+}
+fn query_attestation(
+    deps: Deps,
+    otherchain: String,
+    source_tx_hash: String,
+) -> StdResult<Option<Uint64>> {
+    ATTESTATIONS.may_load(deps.storage, (&otherchain, &source_tx_hash))
+}
+fn query_previewallocation(deps: Deps, id: Uint64) -> StdResult<AllocationPreviewResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let x = projectstates().load(deps.storage, id.u64())?;
+    let community = COMMUNITY.load(deps.storage)?;
+    Ok(calc_tickets(&x, config.decimals, community.len()))
+}
+fn query_lottery(deps: Deps, id: Uint64) -> StdResult<Option<LotteryState>> {
+    LOTTERIES.may_load(deps.storage, id.u64())
+}
+fn query_merkleroot(deps: Deps, id: Uint64) -> StdResult<Option<Binary>> {
+    MERKLE_ROOTS.may_load(deps.storage, id.u64())
+}
@@ -1,12 +1,61 @@
-use cosmwasm_std::{Addr, StdResult, Storage, Uint128, Uint64};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Binary, Empty, Order, StdResult, Storage, Uint128, Uint64};
+use cw20::Expiration;
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
-use Interface::wefund::{Config, ProjectState};
+use Interface::wefund::{
+    AcceptedDenom, BackerState, BondState, Config, LotteryState, Milestone, Position, ProjectState,
+    ProjectStatus, Role, Vote, VoteOption, WhitelistState,
+};
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
 pub const PROJECT_SEQ: Item<Uint64> = Item::new("prj_seq");
-pub const PROJECTSTATES: Map<u64, ProjectState> = Map::new("prj");
+
+//------------projects indexed by status, creator, ecosystem and fundtype-----
+pub struct ProjectIndexes<'a> {
+    pub status: MultiIndex<'a, u8, ProjectState, u64>,
+    pub creator: MultiIndex<'a, Addr, ProjectState, u64>,
+    pub ecosystem: MultiIndex<'a, String, ProjectState, u64>,
+    pub fundtype: MultiIndex<'a, String, ProjectState, u64>,
+}
+
+impl<'a> IndexList<ProjectState> for ProjectIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<ProjectState>> + '_> {
+        let v: Vec<&dyn Index<ProjectState>> =
+            vec![&self.status, &self.creator, &self.ecosystem, &self.fundtype];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn status_key(status: &ProjectStatus) -> u8 {
+    status.clone() as u8
+}
+
+pub fn projectstates<'a>() -> IndexedMap<'a, u64, ProjectState, ProjectIndexes<'a>> {
+    let indexes = ProjectIndexes {
+        status: MultiIndex::new(
+            |x: &ProjectState| status_key(&x.project_status),
+            "prj",
+            "prj__status",
+        ),
+        creator: MultiIndex::new(
+            |x: &ProjectState| x.creator_wallet.clone(),
+            "prj",
+            "prj__creator",
+        ),
+        ecosystem: MultiIndex::new(
+            |x: &ProjectState| x.project_ecosystem.clone(),
+            "prj",
+            "prj__ecosystem",
+        ),
+        fundtype: MultiIndex::new(
+            |x: &ProjectState| x.project_fundtype.clone(),
+            "prj",
+            "prj__fundtype",
+        ),
+    };
+    IndexedMap::new("prj", indexes)
+}
 
 pub fn save_projectstate(store: &mut dyn Storage, _prj: &mut ProjectState) -> StdResult<()> {
     // increment id if exists, or return 1
@@ -15,12 +64,119 @@ pub fn save_projectstate(store: &mut dyn Storage, _prj: &mut ProjectState) -> St
     PROJECT_SEQ.save(store, &id)?;
 
     _prj.project_id = id.clone();
-    PROJECTSTATES.save(store, id.u64(), &_prj)
+    projectstates().save(store, id.u64(), &_prj)
+}
+
+//------------backer positions indexed by holder-------------------------------
+pub const POSITION_SEQ: Item<Uint64> = Item::new("pos_seq");
+
+pub struct PositionIndexes<'a> {
+    pub owner: MultiIndex<'a, Addr, Position, u64>,
 }
 
+impl<'a> IndexList<Position> for PositionIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Position>> + '_> {
+        let v: Vec<&dyn Index<Position>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn positions<'a>() -> IndexedMap<'a, u64, Position, PositionIndexes<'a>> {
+    let indexes = PositionIndexes {
+        owner: MultiIndex::new(|x: &Position| x.owner.clone(), "pos", "pos__owner"),
+    };
+    IndexedMap::new("pos", indexes)
+}
+
+pub fn save_position(store: &mut dyn Storage, position: &mut Position) -> StdResult<()> {
+    // increment id if exists, or return 1
+    let id = POSITION_SEQ.may_load(store)?.unwrap_or_default();
+    let id = id.checked_add(Uint64::new(1))?;
+    POSITION_SEQ.save(store, &id)?;
+
+    position.token_id = id;
+    positions().save(store, id.u64(), position)
+}
+
+//------------positions of a holder in one project-------------------------------
+pub fn held_positions(
+    store: &dyn Storage,
+    project_id: Uint64,
+    owner: &Addr,
+) -> StdResult<Vec<Position>> {
+    let held: StdResult<Vec<_>> = positions()
+        .idx
+        .owner
+        .prefix(owner.clone())
+        .range(store, None, None, Order::Ascending)
+        .collect();
+    Ok(held?
+        .into_iter()
+        .map(|(_, x)| x)
+        .filter(|x| x.project_id == project_id)
+        .collect())
+}
+
+//------------cw721 operators of all positions by (owner, operator)-------------
+pub const OPERATORS: Map<(&Addr, &Addr), Expiration> = Map::new("operators");
+
+//------------backings of each wallet by (project_id, wallet)-------------------
+pub const BACKERS: Map<(u64, &Addr), Vec<BackerState>> = Map::new("backers");
+
+//------------whitelist entries by (project_id, wallet)--------------------------
+pub const WHITELIST: Map<(u64, &Addr), WhitelistState> = Map::new("whitelist");
+
+//------------milestones by (project_id, step)------------------------------------
+pub const MILESTONES: Map<(u64, u64), Milestone> = Map::new("milestones");
+
+//------------last milestone vote of backers by (project_id, wallet)-------------
+pub const VOTES: Map<(u64, &Addr), Vote> = Map::new("votes");
+
+//------------submit bond of each project, kept after removal------------------
+pub const BONDS: Map<u64, BondState> = Map::new("bonds");
+
+//------------approval votes of community members by (project_id, wallet)------
+pub const APPROVAL_VOTES: Map<(u64, &Addr), VoteOption> = Map::new("approval_votes");
+
+//------------community members with a community allocation, snapshot at close--
+pub const WHITELIST_COMMUNITY: Map<u64, Vec<Addr>> = Map::new("whitelist_community");
+
+//------------last whitelist wallet rechecked against its stake----------------
+pub const WHITELIST_RECHECKED: Map<u64, Addr> = Map::new("whitelist_rechecked");
+
+//------------community members eligible to vote, snapshot at proposal----------
+pub const APPROVAL_VOTERS: Map<u64, Vec<Addr>> = Map::new("approval_voters");
+
+//------------merkle root of (wallet, tier, allocation) leaves by project_id----
+pub const MERKLE_ROOTS: Map<u64, Binary> = Map::new("merkle_roots");
+
+//------------merkle whitelist entries by (project_id, wallet), once proven-----
+pub const MERKLE_WHITELIST: Map<(u64, &Addr), WhitelistState> = Map::new("merkle_whitelist");
+
+//------------backed in public sale by (project_id, wallet), capped per wallet--
+pub const PUBLIC_BACKED: Map<(u64, &Addr), Uint128> = Map::new("public_backed");
+
+//------------lottery of oversubscribed whitelist by project_id----------------
+pub const LOTTERIES: Map<u64, LotteryState> = Map::new("lotteries");
+
+//------------roles of each wallet---------------------------------------------
+pub const ROLES: Map<&Addr, Vec<Role>> = Map::new("roles");
+
+//------------secp256k1 keys of relayers signing cross-chain backings----------
+pub const RELAYER_KEYS: Item<Vec<Binary>> = Item::new("relayer_keys");
+
+//------------processed source txs by (otherchain, tx hash), to position-------
+pub const ATTESTATIONS: Map<(&str, &str), Uint64> = Map::new("attestations");
+
 //------------community array------------------------------------------------
 pub const COMMUNITY: Item<Vec<Addr>> = Item::new("community");
 
+//------------denoms and cw20 tokens accepted for backing-----------------------
+pub const ACCEPTED_DENOMS: Map<&str, AcceptedDenom> = Map::new("accepted_denoms");
+
+//------------cw20 tokens ever accepted, escrow in them is paid out after removal--
+pub const CW20_TOKENS: Map<&str, Empty> = Map::new("cw20_tokens");
+
 //------------Profit------------------------------------------------------------
 pub const PROFIT: Item<Uint128> = Item::new("profit");
 
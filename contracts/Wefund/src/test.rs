@@ -1,13 +1,42 @@
 use super::*;
-use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{from_binary, Coin, Uint128, Uint64};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, to_binary, to_vec, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Env, Event,
+    OwnedDeps, Response, Uint128, Uint64, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use k256::ecdsa::signature::Signer;
+use k256::ecdsa::{Signature, SigningKey};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use sha2::{Digest, Sha256};
 
-use crate::contract::{execute, instantiate};
+use crate::contract::{execute, instantiate, migrate, revision_hash};
+use crate::migrate::{
+    LegacyConfig, LegacyMilestone, LegacyProjectState, LegacyVote, LEGACY_CONFIG,
+    LEGACY_PROJECTSTATES,
+};
 use crate::query::query;
+use crate::state::{COMMUNITY, PROJECT_SEQ};
+use cw2::set_contract_version;
+use Interface::cw721::{Cw721ReceiveMsg, OwnerOfResponse, TokensResponse};
+use Interface::events::{
+    ATTR_AMOUNT, ATTR_BACKED, ATTR_COMMUNITY_TICKET, ATTR_CREATOR, ATTR_HOLDER_TICKET, ATTR_KIND,
+    ATTR_NEW_STATUS, ATTR_OLD_STATUS, ATTR_OPTION, ATTR_PROJECT_ID, ATTR_REVISION, ATTR_ROUND,
+    ATTR_STAGE, ATTR_STEP, ATTR_TOKEN_AMOUNT, ATTR_TOKEN_ID, ATTR_WALLET, ATTR_WEIGHT,
+    EVENT_ALLOCATION, EVENT_BACKING, EVENT_MILESTONE_RELEASED, EVENT_PROJECT_CREATED,
+    EVENT_PROJECT_STATUS, EVENT_PROJECT_UPDATED, EVENT_VOTE_CAST,
+};
+use Interface::merkle::{leaf_hash, merkle_proof, merkle_root};
 use Interface::staking::CardType;
-use Interface::wefund::{Config, ExecuteMsg, InstantiateMsg, Milestone, ProjectState, QueryMsg};
+use Interface::wefund::{
+    AllocationPreviewResponse, AllocationResponse, ApprovalTallyResponse, Attestation, BackerState,
+    BondState, BondStatus, Config, ExecuteMsg, FeeRecipient, InstantiateMsg, LotterySlots,
+    LotteryState, MigrateMsg, Milestone, MilestoneTallyResponse, Period, Position, ProjectFilter,
+    ProjectInfo, ProjectState, ProjectStatus, ProjectSummary, PublicSale, QueryMsg, ReceiveMsg,
+    Role, RoleInfo, TierWeights, VoteOption, WhitelistProof, WhitelistState,
+};
 
-use crate::mock_querier::mock_dependencies;
+use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
 
 #[test]
 fn workflow() {
@@ -38,6 +67,13 @@ fn workflow() {
         denom: Some("ujunox".to_string()),
         decimals: Some(Uint64::new(6)),
         vesting_contract: Some("vesting".to_string()),
+        staking_contract: Some(String::from("staking")),
+        fee_bps: None,
+        fee_recipients: None,
+        bond_amount: None,
+        bond_token: None,
+        bond_token_amount: None,
+        bond_slash_bps: None,
     };
     //instantiate
     let info = mock_info("creator1", &[]);
@@ -46,7 +82,7 @@ fn workflow() {
     let msg = ExecuteMsg::AddCommunitymember {
         wallet: String::from("community1"),
     };
-    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let res = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
     println!("Add community member{:?}", res);
 
     // let msg = ExecuteMsg::RemoveCommunitymember{
@@ -64,7 +100,6 @@ fn workflow() {
         milestone_enddate: String::from("enddate"),
         milestone_amount: Uint128::new(100),
         milestone_status: Uint128::new(0),
-        milestone_votes: Vec::new(),
     };
     let milestone2 = Milestone {
         milestone_step: Uint128::new(1),
@@ -75,74 +110,91 @@ fn workflow() {
         milestone_enddate: String::from("enddate"),
         milestone_amount: Uint128::new(200),
         milestone_status: Uint128::new(0),
-        milestone_votes: Vec::new(),
     };
     let milestone_states = vec![milestone1, milestone2];
-    let msg = ExecuteMsg::AddProject {
-        project_id: Uint64::zero(),
-        creator_wallet: String::from("terra1emwyg68n0wtglz8ex2n2728fnfzca9xkdc4aka"),
-        project_description: String::from("demo1"),
-        project_collected: Uint128::new(300),
-        project_email: String::from("deme1@gmail.com"),
-        project_title: String::from("demo1"),
-        project_website: String::from("https://demo1"),
-        project_createddate: String::from("20211223"),
-        project_logo: String::from("icon1"),
-        project_whitepaper: String::from("whitepaper"),
-        project_milestones: milestone_states.clone(),
-        project_company: "company".to_string(),
-        project_ecosystem: "terra".to_string(),
-        project_fundtype: "token".to_string(),
-        project_saft: "saft".to_string(),
-        project_teammembers: Vec::new(),
-        vesting: Vec::new(),
-        token_addr: "token1".to_string(),
+    let msg = ExecuteMsg::SubmitProject {
+        project_info: ProjectInfo {
+            creator_wallet: String::from("creator1"),
+            project_description: String::from("demo1"),
+            project_collected: Uint128::new(300),
+            project_softcap: None,
+            project_email: String::from("deme1@gmail.com"),
+            project_title: String::from("demo1"),
+            project_website: String::from("https://demo1"),
+            project_createddate: String::from("20211223"),
+            project_logo: String::from("icon1"),
+            project_whitepaper: String::from("whitepaper"),
+            project_milestones: milestone_states.clone(),
+            project_company: "company".to_string(),
+            project_ecosystem: "terra".to_string(),
+            project_fundtype: "token".to_string(),
+            project_saft: "saft".to_string(),
+            project_teammembers: Vec::new(),
+            vesting: Vec::new(),
+            token_addr: "token1".to_string(),
 
-        country: "_country".to_string(),
-        cofounder_name: "_cofounder_name".to_string(),
-        service_wefund: "_service_wefund".to_string(),
-        service_charity: "_service_charity".to_string(),
-        professional_link: "_professional_link".to_string(),
+            country: "_country".to_string(),
+            cofounder_name: "_cofounder_name".to_string(),
+            service_wefund: "_service_wefund".to_string(),
+            service_charity: "_service_charity".to_string(),
+            professional_link: "_professional_link".to_string(),
+        },
     };
+    //no bond configured, funds sent along are refused
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator1", &[Coin::new(100, "ujunox")]),
+        msg.clone(),
+    );
+    assert!(res.is_err());
+    //project is submitted by its creator
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator2", &[]),
+        msg.clone(),
+    );
+    assert!(res.is_err());
     let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     // assert_eq!(res.messages.len(), 0);
     println!("{:?}", res);
 
-    let msg = ExecuteMsg::AddProject {
+    let msg = ExecuteMsg::UpdateProject {
         project_id: Uint64::new(1),
-        creator_wallet: String::from("creator1"),
-        project_description: String::from("demo1"),
-        project_collected: Uint128::new(300),
-        project_email: String::from("deme1@gmail.com"),
-        project_title: String::from("demo1"),
-        project_website: String::from("https://demo1"),
-        project_createddate: String::from("20211223"),
-        project_logo: String::from("icon1"),
-        project_whitepaper: String::from("whitepaper"),
-        project_milestones: milestone_states,
-        project_company: "company".to_string(),
-        project_ecosystem: "terra".to_string(),
-        project_fundtype: "token".to_string(),
-        project_saft: "saft".to_string(),
-        project_teammembers: Vec::new(),
-        vesting: Vec::new(),
-        token_addr: "token1".to_string(),
+        project_info: ProjectInfo {
+            creator_wallet: String::from("creator1"),
+            project_description: String::from("demo1"),
+            project_collected: Uint128::new(300),
+            project_softcap: None,
+            project_email: String::from("deme1@gmail.com"),
+            project_title: String::from("demo1"),
+            project_website: String::from("https://demo1"),
+            project_createddate: String::from("20211223"),
+            project_logo: String::from("icon1"),
+            project_whitepaper: String::from("whitepaper"),
+            project_milestones: milestone_states,
+            project_company: "company".to_string(),
+            project_ecosystem: "terra".to_string(),
+            project_fundtype: "token".to_string(),
+            project_saft: "saft".to_string(),
+            project_teammembers: Vec::new(),
+            vesting: Vec::new(),
+            token_addr: "token1".to_string(),
 
-        country: "_country".to_string(),
-        cofounder_name: "_cofounder_name".to_string(),
-        service_wefund: "_service_wefund".to_string(),
-        service_charity: "_service_charity".to_string(),
-        professional_link: "_professional_link".to_string(),
+            country: "_country".to_string(),
+            cofounder_name: "_cofounder_name".to_string(),
+            service_wefund: "_service_wefund".to_string(),
+            service_charity: "_service_charity".to_string(),
+            professional_link: "_professional_link".to_string(),
+        },
     };
-    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    //admin hands project over to creator1
+    let res = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
     // assert_eq!(res.messages.len(), 0);
     println!("{:?}", res);
     // //Wefund Approve
-    let info = mock_info("admin", &[]);
-    let msg = ExecuteMsg::WefundApprove {
-        project_id: Uint64::new(1),
-    };
-    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+    let res = approve(&mut deps, 1, None);
     println!("WeFund Approve: {:?}", res);
 
     // let info = mock_info("admin", &[]);
@@ -158,15 +210,20 @@ fn workflow() {
     let msg = ExecuteMsg::OpenWhitelist {
         project_id: Uint64::new(1),
         holder_alloc: Uint128::from(80u128),
+        tier_weights: None,
+        lottery: None,
+        whitelist_period: None,
+        fundraising_period: None,
+        public_sale: None,
     };
     let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     println!("OpenWhitelist:{:?}", res);
 
     //  register whitelist
+    deps.querier.with_card_type("backer1", CardType::Platium);
     let info = mock_info("backer1", &[]);
     let msg = ExecuteMsg::RegisterWhitelist {
         project_id: Uint64::new(1),
-        card_type: CardType::Platium,
     };
     let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     println!("RegisterWhitelist:{:?}", res);
@@ -180,35 +237,63 @@ fn workflow() {
     println!("Close Whitelist:{:?}", res);
 
     // back 2 projct
-    let info = mock_info("backer1", &[Coin::new(100000000, "uusd")]);
+    let info = mock_info("backer1", &[Coin::new(100000000, "ujunox")]);
     let msg = ExecuteMsg::Back2Project {
         project_id: Uint64::new(1),
         backer_wallet: String::from("backer1"),
         otherchain: "ethereum".to_string(),
         otherchain_wallet: "ether_wallet".to_string(),
+        whitelist_proof: None,
         fundraising_stage: Uint128::zero(),
         token_amount: Uint128::new(10),
     };
     let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     println!("back2project:{:?}", res);
 
-    let info = mock_info("community1", &[Coin::new(200000000, "uusd")]);
+    //community allocation is 60_000_000
+    let info = mock_info("community1", &[Coin::new(200000000, "ujunox")]);
+    let msg = ExecuteMsg::Back2Project {
+        project_id: Uint64::new(1),
+        backer_wallet: String::from("community1"),
+        otherchain: "ethereum".to_string(),
+        otherchain_wallet: "ether_wallet".to_string(),
+        whitelist_proof: None,
+        fundraising_stage: Uint128::zero(),
+        token_amount: Uint128::new(10),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert!(res.is_err());
+
+    let info = mock_info("community1", &[Coin::new(63157894, "ujunox")]);
     let msg = ExecuteMsg::Back2Project {
         project_id: Uint64::new(1),
         backer_wallet: String::from("community1"),
         otherchain: "ethereum".to_string(),
         otherchain_wallet: "ether_wallet".to_string(),
+        whitelist_proof: None,
         fundraising_stage: Uint128::zero(),
         token_amount: Uint128::new(10),
     };
     let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     println!("back2project:{:?}", res);
-    let info = mock_info("community1", &[Coin::new(200000000, "uusd")]);
+
+    let msg = QueryMsg::GetAllocation {
+        project_id: Uint64::new(1),
+        wallet: String::from("backer1"),
+    };
+    let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+    let allocation: AllocationResponse = from_binary(&res).unwrap();
+    assert_eq!(allocation.allocation, Uint128::new(240000000));
+    assert_eq!(allocation.backed, Uint128::new(95000000));
+    assert_eq!(allocation.remaining, Uint128::new(145000000));
+
+    let info = mock_info("backer1", &[Coin::new(152631578, "ujunox")]);
     let msg = ExecuteMsg::Back2Project {
         project_id: Uint64::new(1),
-        backer_wallet: String::from("community1"),
+        backer_wallet: String::from("backer1"),
         otherchain: "ethereum".to_string(),
         otherchain_wallet: "ether_wallet".to_string(),
+        whitelist_proof: None,
         fundraising_stage: Uint128::from(1u128),
         token_amount: Uint128::new(10),
     };
@@ -219,43 +304,45 @@ fn workflow() {
     let info = mock_info("backer1", &[]);
     let msg = ExecuteMsg::SetMilestoneVote {
         project_id: Uint64::new(1),
-        wallet: String::from("backer1"),
-        voted: true,
+        option: VoteOption::Yes,
     };
     let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     println!("set milestone vote:{:?}", res);
 
+    //backer2 did not back the project
     let info = mock_info("backer2", &[]);
     let msg = ExecuteMsg::SetMilestoneVote {
         project_id: Uint64::new(1),
-        wallet: String::from("backer2"),
-        voted: true,
+        option: VoteOption::Yes,
     };
-    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
-    println!("set milestone vote:{:?}", res);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert!(res.is_err());
 
     let info = mock_info("backer1", &[]);
     let msg = ExecuteMsg::SetMilestoneVote {
         project_id: Uint64::new(1),
-        wallet: String::from("backer1"),
-        voted: true,
+        option: VoteOption::Yes,
     };
     let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
     println!("set milestone vote:{:?}", res);
     // //-Get Project-----------------
-    let msg = QueryMsg::GetAllProject {};
+    let msg = QueryMsg::GetAllProject {
+        start_after: None,
+        limit: None,
+        filter: None,
+    };
     let allproject = query(deps.as_ref(), mock_env(), msg).unwrap();
 
     let res: Vec<ProjectState> = from_binary(&allproject).unwrap();
     println!("allproject {:?}", res);
+    //all milestones are released, project is done
     let info = mock_info("backer2", &[]);
     let msg = ExecuteMsg::SetMilestoneVote {
         project_id: Uint64::new(1),
-        wallet: String::from("backer2"),
-        voted: true,
+        option: VoteOption::Yes,
     };
-    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
-    println!("set milestone vote:{:?}", res);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), msg);
+    assert!(res.is_err());
 
     // //-Get Project-----------------
     //     let msg = QueryMsg::GetAllProject{};
@@ -292,9 +379,2566 @@ fn workflow() {
     // let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     //-Get Project-----------------
-    let msg = QueryMsg::GetAllProject {};
+    let msg = QueryMsg::GetAllProject {
+        start_after: None,
+        limit: None,
+        filter: None,
+    };
     let allproject = query(deps.as_ref(), mock_env(), msg).unwrap();
 
     let res: Vec<ProjectState> = from_binary(&allproject).unwrap();
     println!("allproject {:?}", res);
 }
+
+fn setup_fundraising(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    fee_bps: Option<Uint64>,
+) {
+    setup_fundraising_with(
+        deps,
+        fee_bps,
+        &[("backer1", CardType::Platium), ("backer2", CardType::Gold)],
+        None,
+        None,
+    );
+}
+
+fn setup_fundraising_with(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    fee_bps: Option<Uint64>,
+    backers: &[(&str, CardType)],
+    project_softcap: Option<Uint128>,
+    fundraising_period: Option<Period>,
+) {
+    let msg = InstantiateMsg {
+        admin: Some(String::from("admin")),
+        wefund: Some(String::from("wefund")),
+        denom: Some("ujunox".to_string()),
+        decimals: Some(Uint64::new(6)),
+        vesting_contract: None,
+        staking_contract: Some(String::from("staking")),
+        fee_bps: None,
+        fee_recipients: None,
+        bond_amount: None,
+        bond_token: None,
+        bond_token_amount: None,
+        bond_slash_bps: None,
+    };
+    let info = mock_info("creator1", &[]);
+    instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let milestone = Milestone {
+        milestone_step: Uint128::new(0),
+        milestone_name: String::from("milestone1"),
+        milestone_type: "type".to_string(),
+        milestone_description: String::from("mileston1"),
+        milestone_startdate: String::from("startdate"),
+        milestone_enddate: String::from("enddate"),
+        milestone_amount: Uint128::new(300),
+        milestone_status: Uint128::new(0),
+    };
+    let msg = ExecuteMsg::SubmitProject {
+        project_info: ProjectInfo {
+            creator_wallet: String::from("creator1"),
+            project_description: String::from("demo1"),
+            project_collected: Uint128::new(300),
+            project_softcap,
+            project_email: String::from("deme1@gmail.com"),
+            project_title: String::from("demo1"),
+            project_website: String::from("https://demo1"),
+            project_createddate: String::from("20211223"),
+            project_logo: String::from("icon1"),
+            project_whitepaper: String::from("whitepaper"),
+            project_milestones: vec![milestone],
+            project_company: "company".to_string(),
+            project_ecosystem: "juno".to_string(),
+            project_fundtype: "token".to_string(),
+            project_saft: "saft".to_string(),
+            project_teammembers: Vec::new(),
+            vesting: Vec::new(),
+            token_addr: "token1".to_string(),
+
+            country: "_country".to_string(),
+            cofounder_name: "_cofounder_name".to_string(),
+            service_wefund: "_service_wefund".to_string(),
+            service_charity: "_service_charity".to_string(),
+            professional_link: "_professional_link".to_string(),
+        },
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::AddCommunitymember {
+        wallet: String::from("community1"),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    approve(deps, 1, fee_bps);
+
+    let msg = ExecuteMsg::OpenWhitelist {
+        project_id: Uint64::new(1),
+        holder_alloc: Uint128::from(80u128),
+        tier_weights: None,
+        lottery: None,
+        whitelist_period: None,
+        fundraising_period,
+        public_sale: None,
+    };
+    execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+    for (wallet, card_type) in backers {
+        deps.querier.with_card_type(wallet, card_type.clone());
+        let msg = ExecuteMsg::RegisterWhitelist {
+            project_id: Uint64::new(1),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(wallet, &[]), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::CloseWhitelist {
+        project_id: Uint64::new(1),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+}
+
+//---------admin proposes, community1 votes yes, tally after voting period---------
+fn approve(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    project_id: u64,
+    fee_bps: Option<Uint64>,
+) -> Response {
+    let msg = ExecuteMsg::ProposeProject {
+        project_id: Uint64::new(project_id),
+        fee_bps,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::SetApprovalVote {
+        project_id: Uint64::new(project_id),
+        option: VoteOption::Yes,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("community1", &[]), msg).unwrap();
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(7 * 24 * 60 * 60);
+    let msg = ExecuteMsg::TallyApproval {
+        project_id: Uint64::new(project_id),
+    };
+    execute(deps.as_mut(), env, mock_info("anyone", &[]), msg).unwrap()
+}
+
+fn back(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    wallet: &str,
+    funds: &[Coin],
+) -> Result<cosmwasm_std::Response, crate::error::ContractError> {
+    let msg = ExecuteMsg::Back2Project {
+        project_id: Uint64::new(1),
+        backer_wallet: String::from(wallet),
+        otherchain: "".to_string(),
+        otherchain_wallet: "".to_string(),
+        whitelist_proof: None,
+        fundraising_stage: Uint128::zero(),
+        token_amount: Uint128::new(10),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info(wallet, funds), msg)
+}
+
+fn query_refundable(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    wallet: &str,
+) -> Vec<Coin> {
+    let msg = QueryMsg::GetRefundable {
+        project_id: Uint64::new(1),
+        wallet: String::from(wallet),
+    };
+    from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
+
+#[test]
+fn fail_and_refund() {
+    let mut deps = mock_dependencies(&[]);
+    setup_fundraising(&mut deps, None);
+
+    back(&mut deps, "backer1", &[Coin::new(100_000_000, "ujunox")]).unwrap();
+    back(&mut deps, "backer2", &[Coin::new(50_000_000, "ujunox")]).unwrap();
+
+    //nothing to refund before the project failed
+    assert_eq!(query_refundable(&deps, "backer1"), vec![]);
+    let msg = ExecuteMsg::ClaimRefund {
+        project_id: Uint64::new(1),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("backer1", &[]),
+        msg.clone(),
+    );
+    assert!(res.is_err());
+
+    //only owner can fail project
+    let fail = ExecuteMsg::FailProject {
+        project_id: Uint64::new(1),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator1", &[]),
+        fail.clone(),
+    );
+    assert!(res.is_err());
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), fail).unwrap();
+
+    assert_eq!(
+        query_refundable(&deps, "backer1"),
+        vec![Coin::new(95_000_000, "ujunox")]
+    );
+    assert_eq!(
+        query_refundable(&deps, "backer2"),
+        vec![Coin::new(47_500_000, "ujunox")]
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("backer1", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("backer1"),
+            amount: vec![Coin::new(95_000_000, "ujunox")],
+        })
+    );
+    assert_eq!(query_refundable(&deps, "backer1"), vec![]);
+
+    //refund can be claimed only once
+    let res = execute(deps.as_mut(), mock_env(), mock_info("backer1", &[]), msg);
+    assert!(res.is_err());
+}
+
+#[test]
+fn back_with_cw20() {
+    let mut deps = mock_dependencies(&[]);
+    setup_fundraising(&mut deps, None);
+
+    let hook = ReceiveMsg::Back2Project {
+        project_id: Uint64::new(1),
+        backer_wallet: String::from("backer1"),
+        otherchain: "".to_string(),
+        otherchain_wallet: "".to_string(),
+        whitelist_proof: None,
+        fundraising_stage: Uint128::zero(),
+        token_amount: Uint128::new(10),
+    };
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: String::from("backer1"),
+        amount: Uint128::new(100_000_000),
+        msg: to_binary(&hook).unwrap(),
+    });
+
+    //not accepted yet
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("usdc", &[]),
+        msg.clone(),
+    );
+    assert!(res.is_err());
+
+    let accept = ExecuteMsg::SetAcceptedDenom {
+        denom: String::from("usdc"),
+        is_cw20: true,
+        rate: Decimal::one(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("backer1", &[]),
+        accept.clone(),
+    );
+    assert!(res.is_err());
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), accept).unwrap();
+
+    let res = execute(deps.as_mut(), mock_env(), mock_info("usdc", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("usdc"),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from("wefund"),
+                amount: Uint128::new(5_000_000),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    let msg = QueryMsg::GetProject {
+        project_id: Uint64::new(1),
+    };
+    let project: ProjectState =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(project.backerbacked_amount, Uint128::new(95_000_000));
+
+    let msg = QueryMsg::GetBacker {
+        project_id: Uint64::new(1),
+        start_after: None,
+        limit: None,
+    };
+    let backers: Vec<BackerState> =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(backers[0].amount, Coin::new(95_000_000, "usdc"));
+
+    //escrow stays a cw20 payout after the token is no longer accepted
+    let msg = ExecuteMsg::RemoveAcceptedDenom {
+        denom: String::from("usdc"),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let fail = ExecuteMsg::FailProject {
+        project_id: Uint64::new(1),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), fail).unwrap();
+    let msg = ExecuteMsg::ClaimRefund {
+        project_id: Uint64::new(1),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("backer1", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("usdc"),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from("backer1"),
+                amount: Uint128::new(95_000_000),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+}
+
+#[test]
+fn back_with_multiple_denoms() {
+    let mut deps = mock_dependencies(&[]);
+    setup_fundraising(&mut deps, None);
+
+    //unexpected denom
+    let res = back(&mut deps, "backer2", &[Coin::new(10_000_000, "uatom")]);
+    assert!(res.is_err());
+
+    let msg = ExecuteMsg::SetAcceptedDenom {
+        denom: String::from("uatom"),
+        is_cw20: false,
+        rate: Decimal::from_ratio(2u128, 1u128),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    back(&mut deps, "backer2", &[Coin::new(10_000_000, "uatom")]).unwrap();
+
+    //extra coins are refunded, unexpected ones rejected
+    let res = back(
+        &mut deps,
+        "backer1",
+        &[Coin::new(100_000_000, "ujunox"), Coin::new(1_000, "uosmo")],
+    );
+    assert!(res.is_err());
+    let res = back(
+        &mut deps,
+        "backer1",
+        &[
+            Coin::new(100_000_000, "ujunox"),
+            Coin::new(1_000_000, "uatom"),
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages.last().unwrap().msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("backer1"),
+            amount: vec![Coin::new(1_000_000, "uatom")],
+        })
+    );
+
+    let msg = QueryMsg::GetProject {
+        project_id: Uint64::new(1),
+    };
+    let project: ProjectState =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(project.backerbacked_amount, Uint128::new(114_000_000));
+    assert_eq!(
+        project.backerbacked_funds,
+        vec![
+            Coin::new(9_500_000, "uatom"),
+            Coin::new(95_000_000, "ujunox")
+        ]
+    );
+
+    let fail = ExecuteMsg::FailProject {
+        project_id: Uint64::new(1),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), fail).unwrap();
+    assert_eq!(
+        query_refundable(&deps, "backer1"),
+        vec![Coin::new(95_000_000, "ujunox")]
+    );
+    assert_eq!(
+        query_refundable(&deps, "backer2"),
+        vec![Coin::new(9_500_000, "uatom")]
+    );
+}
+
+#[test]
+fn fee_split() {
+    let mut deps = mock_dependencies(&[]);
+    setup_fundraising(&mut deps, Some(Uint64::new(250)));
+
+    let msg = ExecuteMsg::SetConfig {
+        admin: None,
+        wefund: None,
+        denom: None,
+        decimals: None,
+        vesting_contract: None,
+        staking_contract: None,
+        fee_bps: None,
+        fee_recipients: Some(vec![
+            FeeRecipient {
+                address: String::from("wefund"),
+                weight: Uint64::new(3),
+            },
+            FeeRecipient {
+                address: String::from("treasury"),
+                weight: Uint64::new(1),
+            },
+        ]),
+        bond_amount: None,
+        bond_token: None,
+        bond_token_amount: None,
+        bond_slash_bps: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    //2.5% of 100_000_003 is 2_500_000.075
+    let res = back(&mut deps, "backer1", &[Coin::new(100_000_003, "ujunox")]).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("wefund"),
+            amount: vec![Coin::new(1_875_000, "ujunox")],
+        })
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("treasury"),
+            amount: vec![Coin::new(625_000, "ujunox")],
+        })
+    );
+
+    let msg = QueryMsg::GetProject {
+        project_id: Uint64::new(1),
+    };
+    let project: ProjectState =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(project.backerbacked_amount, Uint128::new(97_500_003));
+}
+
+#[test]
+fn overfunding_refund() {
+    let mut deps = mock_dependencies(&[]);
+    setup_fundraising_with(
+        &mut deps,
+        None,
+        &[("backer1", CardType::Platium)],
+        None,
+        None,
+    );
+
+    back(&mut deps, "community1", &[Coin::new(63157894, "ujunox")]).unwrap();
+
+    //300_000_000 - 5% is 285_000_000 but only 240_000_000 is left to the target
+    let res = back(&mut deps, "backer1", &[Coin::new(300_000_000, "ujunox")]).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("wefund"),
+            amount: vec![Coin::new(12_631_578, "ujunox")],
+        })
+    );
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("backer1"),
+            amount: vec![Coin::new(47_368_422, "ujunox")],
+        })
+    );
+
+    let msg = QueryMsg::GetProject {
+        project_id: Uint64::new(1),
+    };
+    let project: ProjectState =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(project.project_status, ProjectStatus::Releasing);
+    assert_eq!(project.backerbacked_amount, Uint128::new(300_000_000));
+    assert_eq!(project.backer_count, Uint64::new(2));
+
+    //backers are ordered by wallet
+    let msg = QueryMsg::GetBacker {
+        project_id: Uint64::new(1),
+        start_after: None,
+        limit: None,
+    };
+    let backers: Vec<BackerState> =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(backers[0].amount, Coin::new(240_000_000, "ujunox"));
+
+    let msg = QueryMsg::GetBacker {
+        project_id: Uint64::new(1),
+        start_after: Some(String::from("backer1")),
+        limit: Some(1),
+    };
+    let backers: Vec<BackerState> =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(backers.len(), 1);
+    assert_eq!(backers[0].backer_wallet, Addr::unchecked("community1"));
+}
+
+#[test]
+fn finalize_fundraising() {
+    let now = mock_env().block.time.seconds();
+    let mut ended = mock_env();
+    ended.block.time = ended.block.time.plus_seconds(100);
+
+    //soft target 100 reached, soft target 200 missed
+    for (softcap, status) in [
+        (100u128, ProjectStatus::Releasing),
+        (200, ProjectStatus::Fail),
+    ] {
+        let mut deps = mock_dependencies(&[]);
+        setup_fundraising_with(
+            &mut deps,
+            None,
+            &[("backer1", CardType::Platium)],
+            Some(Uint128::new(softcap)),
+            Some(Period {
+                start: Uint64::new(now),
+                end: Uint64::new(now + 100),
+            }),
+        );
+        back(&mut deps, "backer1", &[Coin::new(200_000_000, "ujunox")]).unwrap();
+
+        let msg = ExecuteMsg::FinalizeFundraising {
+            project_id: Uint64::new(1),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            msg.clone(),
+        );
+        assert!(res.is_err());
+
+        //no more backing after the end of fundraising
+        let back_msg = ExecuteMsg::Back2Project {
+            project_id: Uint64::new(1),
+            backer_wallet: String::from("backer1"),
+            otherchain: "".to_string(),
+            otherchain_wallet: "".to_string(),
+            whitelist_proof: None,
+            fundraising_stage: Uint128::zero(),
+            token_amount: Uint128::new(10),
+        };
+        let info = mock_info("backer1", &[Coin::new(1_000_000, "ujunox")]);
+        let res = execute(deps.as_mut(), ended.clone(), info, back_msg);
+        assert!(res.is_err());
+
+        execute(deps.as_mut(), ended.clone(), mock_info("anyone", &[]), msg).unwrap();
+        let msg = QueryMsg::GetProject {
+            project_id: Uint64::new(1),
+        };
+        let project: ProjectState =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(project.project_status, status);
+    }
+}
+
+//---------backer1 95_000_000 and backer2 19_000_000 voting on first milestone---
+fn setup_releasing(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Env {
+    let now = mock_env().block.time.seconds();
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(100);
+
+    setup_fundraising_with(
+        deps,
+        None,
+        &[("backer1", CardType::Platium), ("backer2", CardType::Gold)],
+        Some(Uint128::new(100)),
+        Some(Period {
+            start: Uint64::new(now),
+            end: Uint64::new(now + 100),
+        }),
+    );
+    back(deps, "backer1", &[Coin::new(100_000_000, "ujunox")]).unwrap();
+    back(deps, "backer2", &[Coin::new(20_000_000, "ujunox")]).unwrap();
+
+    let msg = ExecuteMsg::SetVotingConfig {
+        project_id: Uint64::new(1),
+        quorum: Decimal::percent(50),
+        threshold: Decimal::percent(50),
+        vote_period: Uint64::new(100),
+        max_rejections: Uint64::new(2),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::FinalizeFundraising {
+        project_id: Uint64::new(1),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap();
+    env
+}
+
+fn vote(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: &Env,
+    sender: &str,
+    option: VoteOption,
+) -> Result<cosmwasm_std::Response, crate::error::ContractError> {
+    let msg = ExecuteMsg::SetMilestoneVote {
+        project_id: Uint64::new(1),
+        option,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), msg)
+}
+
+#[test]
+fn weighted_milestone_vote() {
+    let mut deps = mock_dependencies(&[]);
+    let env = setup_releasing(&mut deps);
+    let tally = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>| {
+        let msg = QueryMsg::GetMilestoneTally {
+            project_id: Uint64::new(1),
+        };
+        let res: MilestoneTallyResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        res
+    };
+
+    //only backers can vote
+    assert!(vote(&mut deps, &env, "anyone", VoteOption::No).is_err());
+
+    vote(&mut deps, &env, "backer2", VoteOption::Yes).unwrap();
+    let res = tally(&deps);
+    assert_eq!(res.yes, Uint128::new(19_000_000));
+    assert_eq!(res.total_weight, Uint128::new(114_000_000));
+    assert!(!res.quorum_reached);
+    assert!(!res.passed);
+
+    vote(&mut deps, &env, "backer1", VoteOption::No).unwrap();
+    let res = tally(&deps);
+    assert!(res.quorum_reached);
+    assert!(!res.passed);
+
+    //abstain leaves backer2 as the only decisive weight
+    let res = vote(&mut deps, &env, "backer1", VoteOption::Abstain).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    let msg = QueryMsg::GetProject {
+        project_id: Uint64::new(1),
+    };
+    let project: ProjectState =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(project.project_status, ProjectStatus::Done);
+}
+
+#[test]
+fn community_backer_votes() {
+    let now = mock_env().block.time.seconds();
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(100);
+    let period = Period {
+        start: Uint64::new(now),
+        end: Uint64::new(now + 100),
+    };
+    let tally = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, env: &Env| {
+        let msg = QueryMsg::GetMilestoneTally {
+            project_id: Uint64::new(1),
+        };
+        let res: MilestoneTallyResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        res
+    };
+    let finalize = ExecuteMsg::FinalizeFundraising {
+        project_id: Uint64::new(1),
+    };
+
+    //no backing, no weight: nothing passes by itself
+    let mut deps = mock_dependencies(&[]);
+    setup_fundraising_with(
+        &mut deps,
+        None,
+        &[],
+        Some(Uint128::zero()),
+        Some(period.clone()),
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        finalize.clone(),
+    )
+    .unwrap();
+    assert!(!tally(&deps, &env).passed);
+
+    //community backers vote with what they backed
+    let mut deps = mock_dependencies(&[]);
+    setup_fundraising_with(&mut deps, None, &[], Some(Uint128::new(100)), Some(period));
+    back(&mut deps, "community1", &[Coin::new(200_000_000, "ujunox")]).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &[]),
+        finalize,
+    )
+    .unwrap();
+    assert_eq!(tally(&deps, &env).total_weight, Uint128::new(190_000_000));
+
+    vote(&mut deps, &env, "community1", VoteOption::Yes).unwrap();
+    let msg = QueryMsg::GetProject {
+        project_id: Uint64::new(1),
+    };
+    let project: ProjectState =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(project.project_status, ProjectStatus::Done);
+}
+
+#[test]
+fn milestone_rejection() {
+    let mut deps = mock_dependencies(&[]);
+    let mut env = setup_releasing(&mut deps);
+    let tally = ExecuteMsg::TallyMilestone {
+        project_id: Uint64::new(1),
+    };
+    let reopen = ExecuteMsg::ReopenMilestoneVote {
+        project_id: Uint64::new(1),
+    };
+
+    for round in 0..2 {
+        vote(&mut deps, &env, "backer1", VoteOption::No).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            tally.clone(),
+        );
+        assert!(res.is_err());
+
+        env.block.time = env.block.time.plus_seconds(100);
+        assert!(vote(&mut deps, &env, "backer2", VoteOption::Yes).is_err());
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("anyone", &[]),
+            tally.clone(),
+        )
+        .unwrap();
+
+        let msg = QueryMsg::GetMilestones {
+            project_id: Uint64::new(1),
+        };
+        let milestones: Vec<Milestone> =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(milestones[0].milestone_status, Uint128::new(3));
+
+        let msg = QueryMsg::GetProject {
+            project_id: Uint64::new(1),
+        };
+        let project: ProjectState =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        if round == 0 {
+            assert_eq!(project.project_status, ProjectStatus::Releasing);
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("anyone", &[]),
+                reopen.clone(),
+            );
+            assert!(res.is_err());
+            //backers reopen without the creator
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("backer2", &[]),
+                reopen.clone(),
+            )
+            .unwrap();
+        } else {
+            assert_eq!(project.project_status, ProjectStatus::Fail);
+        }
+    }
+
+    assert_eq!(
+        query_refundable(&deps, "backer1"),
+        vec![Coin::new(95_000_000, "ujunox")]
+    );
+}
+
+#[test]
+fn project_queries() {
+    let mut deps = mock_dependencies(&[]);
+    setup_fundraising(&mut deps, None);
+    back(&mut deps, "backer1", &[Coin::new(100_000_000, "ujunox")]).unwrap();
+
+    let summaries = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+                     start_after: Option<Uint64>,
+                     filter: ProjectFilter| {
+        let msg = QueryMsg::GetProjectSummaries {
+            start_after,
+            limit: None,
+            filter: Some(filter),
+        };
+        let res: Vec<ProjectSummary> =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        res
+    };
+
+    let res = summaries(
+        &deps,
+        None,
+        ProjectFilter {
+            project_status: Some(ProjectStatus::Fundraising),
+            creator_wallet: Some(String::from("creator1")),
+            ..ProjectFilter::default()
+        },
+    );
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].backer_count, Uint64::new(1));
+    assert_eq!(res[0].backerbacked_amount, Uint128::new(95_000_000));
+
+    let filter = ProjectFilter {
+        project_ecosystem: Some(String::from("terra")),
+        ..ProjectFilter::default()
+    };
+    assert_eq!(summaries(&deps, None, filter), vec![]);
+    let filter = ProjectFilter {
+        project_ecosystem: Some(String::from("juno")),
+        project_fundtype: Some(String::from("token")),
+        ..ProjectFilter::default()
+    };
+    assert_eq!(summaries(&deps, None, filter).len(), 1);
+    let filter = ProjectFilter {
+        project_fundtype: Some(String::from("nft")),
+        ..ProjectFilter::default()
+    };
+    assert_eq!(summaries(&deps, None, filter), vec![]);
+    assert_eq!(
+        summaries(&deps, Some(Uint64::new(1)), ProjectFilter::default()),
+        vec![]
+    );
+}
+
+#[test]
+fn migrate_legacy_state() {
+    let mut deps = mock_dependencies(&[]);
+    set_contract_version(deps.as_mut().storage, "WEFUND", "17.2.3").unwrap();
+    LEGACY_CONFIG
+        .save(
+            deps.as_mut().storage,
+            &LegacyConfig {
+                owner: Addr::unchecked("admin"),
+                wefund: Addr::unchecked("wefund"),
+                denom: String::from("ujunox"),
+                decimals: 6,
+                vesting_contract: Addr::unchecked("vesting"),
+            },
+        )
+        .unwrap();
+    PROJECT_SEQ
+        .save(deps.as_mut().storage, &Uint64::new(1))
+        .unwrap();
+    COMMUNITY
+        .save(deps.as_mut().storage, &vec![Addr::unchecked("community1")])
+        .unwrap();
+
+    let backing = |wallet: &str, amount: u128| BackerState {
+        backer_wallet: Addr::unchecked(wallet),
+        amount: Coin::new(amount, "ujunox"),
+        otherchain: String::new(),
+        otherchain_wallet: String::new(),
+    };
+    let whitelist = |wallet: &str, card_type: CardType, backed: u128| WhitelistState {
+        wallet: Addr::unchecked(wallet),
+        card_type,
+        allocation: Uint128::new(200_000_000),
+        backed: Uint128::new(backed),
+    };
+    let milestone = |step: u128, status: u128, votes: Vec<LegacyVote>| LegacyMilestone {
+        milestone_step: Uint128::new(step),
+        milestone_name: String::from("milestone"),
+        milestone_type: String::new(),
+        milestone_description: String::new(),
+        milestone_startdate: String::new(),
+        milestone_enddate: String::new(),
+        milestone_amount: Uint128::new(100),
+        milestone_status: Uint128::new(status),
+        milestone_votes: votes,
+    };
+    let legacy = LegacyProjectState {
+        project_company: String::from("company"),
+        project_title: String::from("title"),
+        project_description: String::new(),
+        project_ecosystem: String::from("Juno"),
+        project_fundtype: String::new(),
+        project_createddate: String::new(),
+        project_saft: String::new(),
+        project_logo: String::new(),
+        project_whitepaper: String::new(),
+        project_website: String::new(),
+        project_email: String::new(),
+        country: String::new(),
+        cofounder_name: String::new(),
+        service_wefund: String::new(),
+        service_charity: String::new(),
+        professional_link: String::new(),
+        project_id: Uint64::new(1),
+        creator_wallet: Addr::unchecked("creator1"),
+        project_collected: Uint128::new(300),
+        project_status: ProjectStatus::Releasing,
+        fundraising_stage: Uint128::zero(),
+        //first milestone of 100 was released out of 300 backed
+        backerbacked_amount: Uint128::new(200_000_000),
+        backer_states: vec![
+            backing("backer1", 100_000_000),
+            backing("backer2", 60_000_000),
+            backing("backer1", 100_000_000),
+            backing("community1", 40_000_000),
+        ],
+        milestone_states: vec![
+            milestone(0, 2, Vec::new()),
+            milestone(
+                1,
+                0,
+                vec![
+                    LegacyVote {
+                        wallet: Addr::unchecked("backer1"),
+                        voted: true,
+                    },
+                    LegacyVote {
+                        wallet: Addr::unchecked("backer2"),
+                        voted: false,
+                    },
+                    LegacyVote {
+                        wallet: Addr::unchecked("admin"),
+                        voted: true,
+                    },
+                ],
+            ),
+        ],
+        project_milestonestep: Uint128::new(1),
+        teammember_states: Vec::new(),
+        vesting: Vec::new(),
+        token_addr: Addr::unchecked("token1"),
+        whitelist: vec![
+            whitelist("backer1", CardType::Platium, 200_000_000),
+            whitelist("backer2", CardType::Gold, 60_000_000),
+            whitelist("community1", CardType::Other, 40_000_000),
+        ],
+        holder_alloc: Uint128::new(80),
+        holder_ticket: Uint128::zero(),
+        community_ticket: Uint128::zero(),
+    };
+    LEGACY_PROJECTSTATES
+        .save(deps.as_mut().storage, 1, &legacy)
+        .unwrap();
+
+    //other contract or newer version is refused
+    set_contract_version(deps.as_mut().storage, "WEFUND", "99.0.0").unwrap();
+    assert!(migrate(deps.as_mut(), mock_env(), MigrateMsg {}).is_err());
+    set_contract_version(deps.as_mut().storage, "Vesting", "17.2.3").unwrap();
+    assert!(migrate(deps.as_mut(), mock_env(), MigrateMsg {}).is_err());
+
+    set_contract_version(deps.as_mut().storage, "WEFUND", "17.2.3").unwrap();
+    migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    let msg = QueryMsg::GetProject {
+        project_id: Uint64::new(1),
+    };
+    let project: ProjectState =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(project.backer_count, Uint64::new(3));
+    assert_eq!(project.milestone_count, Uint64::new(2));
+    assert_eq!(project.whitelist_weight, Uint128::new(170));
+    //first milestone of 100 was released
+    assert_eq!(project.backerbacked_amount, Uint128::new(200_000_000));
+    assert_eq!(
+        project.backerbacked_total_funds,
+        vec![Coin::new(300_000_000, "ujunox")]
+    );
+    assert_eq!(
+        project.backerbacked_funds,
+        vec![Coin::new(200_000_000, "ujunox")]
+    );
+
+    let msg = QueryMsg::GetBacker {
+        project_id: Uint64::new(1),
+        start_after: None,
+        limit: None,
+    };
+    let backers: Vec<BackerState> =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(backers.len(), 4);
+
+    let msg = QueryMsg::GetMilestones {
+        project_id: Uint64::new(1),
+    };
+    let milestones: Vec<Milestone> =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(milestones[0].milestone_status, Uint128::new(2));
+    assert_eq!(milestones[1].milestone_status, Uint128::zero());
+
+    //yes vote of backer1 is kept, owner vote is dropped, all backings weigh
+    let msg = QueryMsg::GetMilestoneTally {
+        project_id: Uint64::new(1),
+    };
+    let tally: MilestoneTallyResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(tally.yes, Uint128::new(200_000_000));
+    assert_eq!(tally.total_weight, Uint128::new(300_000_000));
+
+    //project is reachable through status index
+    let msg = QueryMsg::GetAllProject {
+        start_after: None,
+        limit: None,
+        filter: Some(ProjectFilter {
+            project_status: Some(ProjectStatus::Releasing),
+            ..ProjectFilter::default()
+        }),
+    };
+    let res: Vec<ProjectState> =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.len(), 1);
+
+    let config: Config =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetConfig {}).unwrap()).unwrap();
+    assert_eq!(config.fee_bps, 500);
+
+    //legacy position moves without a vesting transfer, tokens vest per wallet
+    let owner: OwnerOfResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::OwnerOf {
+                token_id: String::from("1"),
+                include_expired: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let msg = ExecuteMsg::TransferNft {
+        recipient: String::from("backer9"),
+        token_id: String::from("1"),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info(&owner.owner, &[]), msg).unwrap();
+    assert!(res.messages.is_empty());
+}
+
+#[test]
+fn project_status_transitions() {
+    let mut deps = mock_dependencies(&[]);
+    setup_fundraising(&mut deps, None);
+
+    //fundraising can't go back to whitelist
+    let msg = ExecuteMsg::SetProjectStatus {
+        project_id: Uint64::new(1),
+        status: ProjectStatus::Whitelist,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg);
+    assert!(res.is_err());
+
+    //releasing and public sale are only reached through their own messages
+    for status in [ProjectStatus::Releasing, ProjectStatus::PublicSale] {
+        let msg = ExecuteMsg::SetProjectStatus {
+            project_id: Uint64::new(1),
+            status,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg);
+        assert!(res.is_err());
+    }
+
+    let msg = ExecuteMsg::FailProject {
+        project_id: Uint64::new(1),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new("project_status")
+            .add_attribute("project_id", "1")
+            .add_attribute("old_status", "Fundraising")
+            .add_attribute("new_status", "Fail")]
+    );
+
+    //fail is final
+    let msg = ExecuteMsg::SetProjectStatus {
+        project_id: Uint64::new(1),
+        status: ProjectStatus::Releasing,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg);
+    assert!(res.is_err());
+}
+
+#[test]
+fn transfer_all_coins() {
+    let mut deps = mock_dependencies(&[Coin::new(200_000_000, "ujunox")]);
+    setup_fundraising(&mut deps, None);
+    back(&mut deps, "backer1", &[Coin::new(100_000_000, "ujunox")]).unwrap();
+
+    let msg = ExecuteMsg::TransferAllCoins {
+        wallet: String::from("treasury"),
+    };
+    let grant = ExecuteMsg::GrantRole {
+        wallet: String::from("admin2"),
+        role: Role::Admin,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), grant).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin2", &[]),
+        msg.clone(),
+    );
+    assert!(res.is_err());
+
+    //escrow of 95 stays in the contract
+    let res = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("treasury"),
+            amount: vec![Coin::new(105_000_000, "ujunox")],
+        })
+    );
+}
+
+#[test]
+fn role_gated_actions() {
+    let mut deps = mock_dependencies(&[]);
+    setup_fundraising(&mut deps, None);
+
+    let add_member = ExecuteMsg::AddCommunitymember {
+        wallet: String::from("community2"),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("manager", &[]),
+        add_member.clone(),
+    );
+    assert!(res.is_err());
+
+    //only admin grants roles
+    let grant = ExecuteMsg::GrantRole {
+        wallet: String::from("manager"),
+        role: Role::CommunityManager,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("manager", &[]),
+        grant.clone(),
+    );
+    assert!(res.is_err());
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), grant).unwrap();
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("manager", &[]),
+        add_member,
+    )
+    .unwrap();
+
+    //community manager is not an operator
+    let msg = ExecuteMsg::SetFundraisingStage {
+        project_id: Uint64::new(1),
+        stage: Uint128::new(1),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("manager", &[]), msg);
+    assert!(res.is_err());
+
+    let msg = QueryMsg::GetRoles {
+        start_after: None,
+        limit: None,
+    };
+    let res: Vec<RoleInfo> = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(
+        res,
+        vec![RoleInfo {
+            wallet: Addr::unchecked("manager"),
+            roles: vec![Role::CommunityManager],
+        }]
+    );
+
+    let revoke = ExecuteMsg::RevokeRole {
+        wallet: String::from("manager"),
+        role: Role::CommunityManager,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), revoke).unwrap();
+    let msg = ExecuteMsg::RemoveCommunitymember {
+        wallet: String::from("community2"),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("manager", &[]), msg);
+    assert!(res.is_err());
+
+    //admin role doesn't take over ownership
+    let grant = ExecuteMsg::GrantRole {
+        wallet: String::from("admin2"),
+        role: Role::Admin,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), grant).unwrap();
+    let msg = ExecuteMsg::SetConfig {
+        admin: Some(String::from("admin2")),
+        wefund: None,
+        denom: None,
+        decimals: None,
+        vesting_contract: None,
+        staking_contract: None,
+        fee_bps: None,
+        fee_recipients: None,
+        bond_amount: None,
+        bond_token: None,
+        bond_token_amount: None,
+        bond_slash_bps: None,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("admin2", &[]), msg);
+    assert!(res.is_err());
+}
+
+fn project_info(creator_wallet: &str, milestone_amount: u128) -> ProjectInfo {
+    ProjectInfo {
+        project_company: "company".to_string(),
+        project_title: String::from("demo1"),
+        project_description: String::from("demo1"),
+        project_ecosystem: "juno".to_string(),
+        project_fundtype: "token".to_string(),
+        project_createddate: String::from("20211223"),
+        project_saft: "saft".to_string(),
+        project_logo: String::from("icon1"),
+        project_whitepaper: String::from("whitepaper"),
+        project_website: String::from("https://demo1"),
+        project_email: String::from("deme1@gmail.com"),
+        creator_wallet: String::from(creator_wallet),
+        project_collected: Uint128::new(milestone_amount),
+        project_softcap: None,
+        project_milestones: vec![Milestone {
+            milestone_step: Uint128::new(0),
+            milestone_name: String::from("milestone1"),
+            milestone_type: "type".to_string(),
+            milestone_description: String::from("mileston1"),
+            milestone_startdate: String::from("startdate"),
+            milestone_enddate: String::from("enddate"),
+            milestone_amount: Uint128::new(milestone_amount),
+            milestone_status: Uint128::new(0),
+        }],
+        project_teammembers: Vec::new(),
+        vesting: Vec::new(),
+        token_addr: "token1".to_string(),
+
+        country: "_country".to_string(),
+        cofounder_name: "_cofounder_name".to_string(),
+        service_wefund: "_service_wefund".to_string(),
+        service_charity: "_service_charity".to_string(),
+        professional_link: "_professional_link".to_string(),
+    }
+}
+
+#[test]
+fn submit_and_update_project() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        admin: Some(String::from("admin")),
+        wefund: Some(String::from("wefund")),
+        denom: Some("ujunox".to_string()),
+        decimals: Some(Uint64::new(6)),
+        vesting_contract: None,
+        staking_contract: Some(String::from("staking")),
+        fee_bps: None,
+        fee_recipients: None,
+        bond_amount: None,
+        bond_token: None,
+        bond_token_amount: None,
+        bond_slash_bps: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    //submit always creates a new project
+    for id in 1..3u64 {
+        let msg = ExecuteMsg::SubmitProject {
+            project_info: project_info("creator1", 300),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+        assert_eq!(res.attributes[1].value, id.to_string());
+        assert_eq!(res.events[0].ty, EVENT_PROJECT_CREATED);
+    }
+
+    //only creator or admin updates
+    let update = ExecuteMsg::UpdateProject {
+        project_id: Uint64::new(1),
+        project_info: project_info("creator1", 500),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        update.clone(),
+    );
+    assert!(res.is_err());
+
+    //creator can't hand the project over
+    let msg = ExecuteMsg::UpdateProject {
+        project_id: Uint64::new(1),
+        project_info: project_info("anyone", 300),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg);
+    assert!(res.is_err());
+
+    let before: ProjectState = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetProject {
+                project_id: Uint64::new(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator1", &[]),
+        update.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new(EVENT_PROJECT_UPDATED)
+            .add_attribute(ATTR_PROJECT_ID, "1")
+            .add_attribute(ATTR_CREATOR, "creator1")
+            .add_attribute(ATTR_REVISION, "1")]
+    );
+
+    let x: ProjectState = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetProject {
+                project_id: Uint64::new(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(x.revision, Uint64::new(1));
+    assert_eq!(x.project_collected, Uint128::new(500));
+    let old_milestones = project_info("creator1", 300).project_milestones;
+    assert_eq!(
+        x.previous_hash,
+        revision_hash(&before, &old_milestones).unwrap()
+    );
+
+    //no updates after proposal
+    let msg = ExecuteMsg::AddCommunitymember {
+        wallet: String::from("community1"),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    approve(&mut deps, 1, None);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator1", &[]),
+        update,
+    );
+    assert!(res.is_err());
+}
+
+#[test]
+fn submission_bond() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        admin: Some(String::from("admin")),
+        wefund: Some(String::from("wefund")),
+        denom: Some("ujunox".to_string()),
+        decimals: Some(Uint64::new(6)),
+        vesting_contract: None,
+        staking_contract: Some(String::from("staking")),
+        fee_bps: None,
+        fee_recipients: None,
+        bond_amount: Some(Uint128::new(100)),
+        bond_token: Some(String::from("wfd")),
+        bond_token_amount: Some(Uint128::new(1000)),
+        bond_slash_bps: Some(Uint64::new(5000)),
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+
+    //no bond, no project
+    let submit = ExecuteMsg::SubmitProject {
+        project_info: project_info("creator1", 300),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator1", &[Coin::new(50, "ujunox")]),
+        submit.clone(),
+    );
+    assert!(res.is_err());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator1", &[Coin::new(100, "ujunox")]),
+        submit,
+    )
+    .unwrap();
+
+    //bond in WFD token
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: String::from("creator2"),
+        amount: Uint128::new(1000),
+        msg: to_binary(&ReceiveMsg::SubmitProject {
+            project_info: project_info("creator2", 300),
+        })
+        .unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info("wfd", &[]), msg).unwrap();
+
+    //approve returns the bond
+    let msg = ExecuteMsg::AddCommunitymember {
+        wallet: String::from("community1"),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let res = approve(&mut deps, 1, None);
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("creator1"),
+            amount: vec![Coin::new(100, "ujunox")],
+        })
+    );
+
+    //removal slashes half of the bond to wefund
+    let msg = ExecuteMsg::RemoveProject {
+        project_id: Uint64::new(2),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from("wfd"),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: String::from("wefund"),
+                amount: Uint128::new(500),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+    assert_eq!(res.messages.len(), 2);
+
+    //approved project can't be removed any more
+    let msg = ExecuteMsg::RemoveProject {
+        project_id: Uint64::new(1),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg);
+    assert!(res.is_err());
+
+    let bond: Option<BondState> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBond {
+                project_id: Uint64::new(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(bond.unwrap().status, BondStatus::Returned);
+    let bond: Option<BondState> = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetBond {
+                project_id: Uint64::new(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let bond = bond.unwrap();
+    assert_eq!(bond.status, BondStatus::Slashed);
+    assert_eq!(bond.slashed, Uint128::new(500));
+}
+
+#[test]
+fn community_approval() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        admin: Some(String::from("admin")),
+        wefund: Some(String::from("wefund")),
+        denom: Some("ujunox".to_string()),
+        decimals: Some(Uint64::new(6)),
+        vesting_contract: None,
+        staking_contract: Some(String::from("staking")),
+        fee_bps: None,
+        fee_recipients: None,
+        bond_amount: None,
+        bond_token: None,
+        bond_token_amount: None,
+        bond_slash_bps: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    for wallet in ["community1", "community2"] {
+        let msg = ExecuteMsg::AddCommunitymember {
+            wallet: String::from(wallet),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    }
+    for _ in 0..2 {
+        let msg = ExecuteMsg::SubmitProject {
+            project_info: project_info("creator1", 300),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+    }
+
+    //only admin sets fee on proposal
+    let msg = ExecuteMsg::ProposeProject {
+        project_id: Uint64::new(1),
+        fee_bps: Some(Uint64::zero()),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg);
+    assert!(res.is_err());
+    let msg = ExecuteMsg::ProposeProject {
+        project_id: Uint64::new(1),
+        fee_bps: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+
+    //only community members vote
+    let no_vote = ExecuteMsg::SetApprovalVote {
+        project_id: Uint64::new(1),
+        option: VoteOption::No,
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        no_vote.clone(),
+    );
+    assert!(res.is_err());
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("community1", &[]),
+        no_vote.clone(),
+    )
+    .unwrap();
+
+    //members added after the proposal don't vote on it
+    let msg = ExecuteMsg::AddCommunitymember {
+        wallet: String::from("community3"),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("community3", &[]),
+        no_vote,
+    );
+    assert!(res.is_err());
+
+    let tally = ExecuteMsg::TallyApproval {
+        project_id: Uint64::new(1),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        tally.clone(),
+    );
+    assert!(res.is_err());
+
+    let res: ApprovalTallyResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetApprovalTally {
+                project_id: Uint64::new(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.no, Uint64::new(1));
+    assert_eq!(res.total, Uint64::new(2));
+    assert!(res.quorum_reached);
+    assert!(!res.passed);
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(7 * 24 * 60 * 60);
+    execute(deps.as_mut(), env, mock_info("anyone", &[]), tally).unwrap();
+    let x: ProjectState = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetProject {
+                project_id: Uint64::new(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(x.project_status, ProjectStatus::Rejected);
+    assert_eq!(x.rejected_reason, "threshold not reached");
+
+    //owner veto
+    let veto = ExecuteMsg::VetoProject {
+        project_id: Uint64::new(2),
+        reason: String::from("scam"),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator1", &[]),
+        veto.clone(),
+    );
+    assert!(res.is_err());
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), veto).unwrap();
+    let x: ProjectState = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetProject {
+                project_id: Uint64::new(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(x.project_status, ProjectStatus::Rejected);
+    assert_eq!(x.rejected_reason, "scam");
+}
+
+#[test]
+fn transfer_position() {
+    let mut deps = mock_dependencies(&[]);
+    let env = setup_releasing(&mut deps);
+
+    vote(&mut deps, &env, "backer1", VoteOption::No).unwrap();
+    let msg = ExecuteMsg::TransferNft {
+        recipient: String::from("backer3"),
+        token_id: String::from("1"),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("backer2", &[]),
+        msg.clone(),
+    );
+    assert!(res.is_err());
+
+    //approved spender transfers on behalf of the owner
+    let approve = ExecuteMsg::Approve {
+        spender: String::from("backer2"),
+        token_id: String::from("1"),
+        expires: None,
+    };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("backer1", &[]),
+        approve,
+    )
+    .unwrap();
+    execute(deps.as_mut(), env.clone(), mock_info("backer2", &[]), msg).unwrap();
+
+    let owner: OwnerOfResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::OwnerOf {
+                token_id: String::from("1"),
+                include_expired: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(owner.owner, "backer3");
+    assert!(owner.approvals.is_empty());
+    let tokens: TokensResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Tokens {
+                owner: String::from("backer3"),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(tokens.tokens, vec![String::from("1")]);
+
+    //vote weight leaves with the position
+    let msg = QueryMsg::GetMilestoneTally {
+        project_id: Uint64::new(1),
+    };
+    let res: MilestoneTallyResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.no, Uint128::zero());
+    assert!(vote(&mut deps, &env, "backer1", VoteOption::No).is_err());
+    vote(&mut deps, &env, "backer3", VoteOption::No).unwrap();
+
+    let msg = QueryMsg::GetPositions {
+        owner: String::from("backer3"),
+        project_id: Some(Uint64::new(1)),
+        start_after: None,
+        limit: None,
+    };
+    let res: Vec<Position> = from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].token_id, Uint64::new(1));
+    assert_eq!(res[0].weight, Uint128::new(95_000_000));
+
+    //refund goes to the holder
+    let msg = ExecuteMsg::FailProject {
+        project_id: Uint64::new(1),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("admin", &[]), msg).unwrap();
+    let msg = ExecuteMsg::ClaimRefund {
+        project_id: Uint64::new(1),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("backer1", &[]),
+        msg.clone(),
+    );
+    assert!(res.is_err());
+    let res = execute(deps.as_mut(), env.clone(), mock_info("backer3", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("backer3"),
+            amount: vec![Coin::new(95_000_000, "ujunox")],
+        })
+    );
+
+    //operator sends the position to a contract, which is notified
+    let msg = ExecuteMsg::ApproveAll {
+        operator: String::from("market"),
+        expires: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("backer3", &[]), msg).unwrap();
+    let msg = ExecuteMsg::SendNft {
+        contract: String::from("escrow"),
+        token_id: String::from("1"),
+        msg: Binary::from(b"{}".to_vec()),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info("market", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages.last().unwrap().msg,
+        Cw721ReceiveMsg {
+            sender: String::from("market"),
+            token_id: String::from("1"),
+            msg: Binary::from(b"{}".to_vec()),
+        }
+        .into_cosmos_msg("escrow")
+        .unwrap()
+    );
+    let msg = QueryMsg::AllTokens {
+        start_after: None,
+        limit: None,
+    };
+    let res: TokensResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    assert_eq!(res.tokens, vec![String::from("1"), String::from("2")]);
+}
+
+#[test]
+fn signed_attestation() {
+    let mut deps = mock_dependencies(&[]);
+    setup_fundraising(&mut deps, None);
+
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]).unwrap();
+    let pubkey = Binary::from(
+        signing_key
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes(),
+    );
+    let attestation = Attestation {
+        project_id: Uint64::new(1),
+        backer_wallet: String::from("backer1"),
+        denom: String::from("ujunox"),
+        amount: Uint128::new(10_000_000),
+        fundraising_stage: Uint128::zero(),
+        token_amount: Uint128::new(100),
+        otherchain: String::from("ethereum"),
+        otherchain_wallet: String::from("0xbacker1"),
+        source_tx_hash: String::from("0xtx1"),
+        chain_id: mock_env().block.chain_id,
+        contract: String::from(MOCK_CONTRACT_ADDR),
+    };
+    let sign = |attestation: &Attestation| {
+        let signature: Signature = signing_key.sign(&to_vec(attestation).unwrap());
+        Binary::from(signature.as_ref())
+    };
+
+    let grant = ExecuteMsg::GrantRole {
+        wallet: String::from("relayer"),
+        role: Role::Relayer,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), grant).unwrap();
+
+    //key must be registered
+    let msg = ExecuteMsg::Back2ProjectWithout {
+        attestation: attestation.clone(),
+        pubkey: pubkey.clone(),
+        signature: sign(&attestation),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("relayer", &[]),
+        msg.clone(),
+    );
+    assert!(res.is_err());
+    let add_key = ExecuteMsg::AddRelayerKey {
+        pubkey: pubkey.clone(),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), add_key).unwrap();
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("relayer", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+
+    //no replay
+    let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg);
+    assert!(res.is_err());
+
+    //signature covers the amount
+    let mut tampered = attestation.clone();
+    tampered.source_tx_hash = String::from("0xtx2");
+    let signature = sign(&tampered);
+    tampered.amount = Uint128::new(20_000_000);
+    let msg = ExecuteMsg::Back2ProjectWithout {
+        attestation: tampered,
+        pubkey: pubkey.clone(),
+        signature,
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg);
+    assert!(res.is_err());
+
+    //signature made for another chain doesn't back here
+    let mut other = attestation.clone();
+    other.source_tx_hash = String::from("0xtx3");
+    other.chain_id = String::from("other-chain");
+    let msg = ExecuteMsg::Back2ProjectWithout {
+        attestation: other.clone(),
+        pubkey: pubkey.clone(),
+        signature: sign(&other),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg);
+    assert!(res.is_err());
+
+    //surplus can't go back to the other chain
+    let mut over = attestation.clone();
+    over.source_tx_hash = String::from("0xtx4");
+    over.amount = Uint128::new(1_000_000_000);
+    let msg = ExecuteMsg::Back2ProjectWithout {
+        attestation: over.clone(),
+        pubkey: pubkey.clone(),
+        signature: sign(&over),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg);
+    assert!(res.is_err());
+
+    //attested funds count to the target, but nothing is escrowed here
+    let x: ProjectState = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetProject {
+                project_id: Uint64::new(1),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(x.crosschain_backed_amount, Uint128::new(9_500_000));
+    assert_eq!(x.backerbacked_amount, Uint128::zero());
+    assert!(x.backerbacked_funds.is_empty());
+    assert_eq!(x.milestone_total_weight, Uint128::new(9_500_000));
+
+    //only fundraising projects take backings, no refund of attested funds here
+    let msg = ExecuteMsg::FailProject {
+        project_id: Uint64::new(1),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let msg = ExecuteMsg::ClaimRefund {
+        project_id: Uint64::new(1),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("backer1", &[]), msg);
+    assert!(res.is_err());
+    over.source_tx_hash = String::from("0xtx5");
+    over.amount = Uint128::new(1_000_000);
+    let msg = ExecuteMsg::Back2ProjectWithout {
+        attestation: over.clone(),
+        pubkey: pubkey.clone(),
+        signature: sign(&over),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), msg);
+    assert!(res.is_err());
+
+    let msg = QueryMsg::GetAttestation {
+        otherchain: String::from("ethereum"),
+        source_tx_hash: String::from("0xtx1"),
+    };
+    let res: Option<Uint64> = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res, Some(Uint64::new(1)));
+}
+
+#[test]
+fn allocation_preview() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        admin: Some(String::from("admin")),
+        wefund: Some(String::from("wefund")),
+        denom: Some("ujunox".to_string()),
+        decimals: Some(Uint64::new(6)),
+        vesting_contract: None,
+        staking_contract: Some(String::from("staking")),
+        fee_bps: None,
+        fee_recipients: None,
+        bond_amount: None,
+        bond_token: None,
+        bond_token_amount: None,
+        bond_slash_bps: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let msg = ExecuteMsg::AddCommunitymember {
+        wallet: String::from("community1"),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let msg = ExecuteMsg::SubmitProject {
+        project_info: project_info("creator1", 300),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+    approve(&mut deps, 1, None);
+
+    //split is a percent
+    let open = |holder_alloc: u128| ExecuteMsg::OpenWhitelist {
+        project_id: Uint64::new(1),
+        holder_alloc: Uint128::new(holder_alloc),
+        tier_weights: Some(TierWeights {
+            platium: Uint128::new(3),
+            gold: Uint128::new(1),
+            silver: Uint128::zero(),
+            bronze: Uint128::zero(),
+        }),
+        lottery: None,
+        whitelist_period: None,
+        fundraising_period: None,
+        public_sale: None,
+    };
+    let info = mock_info("creator1", &[]);
+    assert!(execute(deps.as_mut(), mock_env(), info.clone(), open(101)).is_err());
+    execute(deps.as_mut(), mock_env(), info.clone(), open(80)).unwrap();
+
+    let preview = |deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>| {
+        let msg = QueryMsg::PreviewAllocation {
+            project_id: Uint64::new(1),
+        };
+        let res: AllocationPreviewResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        res
+    };
+
+    //no holder registered, community takes the whole target
+    let res = preview(&deps);
+    assert_eq!(res.holder_pool, Uint128::zero());
+    assert_eq!(res.community_ticket, Uint128::new(300_000_000));
+
+    for (wallet, card_type) in [("backer1", CardType::Platium), ("backer2", CardType::Gold)] {
+        deps.querier.with_card_type(wallet, card_type);
+        let msg = ExecuteMsg::RegisterWhitelist {
+            project_id: Uint64::new(1),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(wallet, &[]), msg).unwrap();
+    }
+    let res = preview(&deps);
+    assert_eq!(res.whitelist_weight, Uint128::new(4));
+    assert_eq!(res.holder_pool, Uint128::new(240_000_000));
+    assert_eq!(res.holder_ticket, Uint128::new(60_000_000));
+    assert_eq!(res.community_ticket, Uint128::new(60_000_000));
+
+    //registered wallets keep the tier weights they joined on
+    execute(deps.as_mut(), mock_env(), info.clone(), open(80)).unwrap();
+    let msg = ExecuteMsg::OpenWhitelist {
+        project_id: Uint64::new(1),
+        holder_alloc: Uint128::new(80),
+        tier_weights: None,
+        lottery: None,
+        whitelist_period: None,
+        fundraising_period: None,
+        public_sale: None,
+    };
+    assert!(execute(deps.as_mut(), mock_env(), info.clone(), msg).is_err());
+
+    //closing stores what preview showed
+    let msg = ExecuteMsg::CloseWhitelist {
+        project_id: Uint64::new(1),
+    };
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.events[0],
+        Event::new(EVENT_ALLOCATION)
+            .add_attribute(ATTR_PROJECT_ID, "1")
+            .add_attribute(ATTR_HOLDER_TICKET, "60000000")
+            .add_attribute(ATTR_COMMUNITY_TICKET, "60000000")
+    );
+    let msg = QueryMsg::GetWhitelist {
+        project_id: Uint64::new(1),
+        start_after: None,
+        limit: None,
+    };
+    let whitelist: Vec<WhitelistState> =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(whitelist[0].allocation, Uint128::new(180_000_000));
+    assert_eq!(whitelist[1].allocation, Uint128::new(60_000_000));
+
+    //members added after close share no community ticket
+    let msg = ExecuteMsg::AddCommunitymember {
+        wallet: String::from("community2"),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    for (wallet, allocation) in [("community1", 60_000_000), ("community2", 0)] {
+        let msg = QueryMsg::GetAllocation {
+            project_id: Uint64::new(1),
+            wallet: String::from(wallet),
+        };
+        let res: AllocationResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res.allocation, Uint128::new(allocation));
+    }
+}
+
+#[test]
+fn whitelist_tier_from_staking() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        admin: Some(String::from("admin")),
+        wefund: Some(String::from("wefund")),
+        denom: Some("ujunox".to_string()),
+        decimals: Some(Uint64::new(6)),
+        vesting_contract: None,
+        staking_contract: Some(String::from("staking")),
+        fee_bps: None,
+        fee_recipients: None,
+        bond_amount: None,
+        bond_token: None,
+        bond_token_amount: None,
+        bond_slash_bps: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let msg = ExecuteMsg::AddCommunitymember {
+        wallet: String::from("community1"),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let msg = ExecuteMsg::SubmitProject {
+        project_info: project_info("creator1", 300),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+    approve(&mut deps, 1, None);
+    let msg = ExecuteMsg::OpenWhitelist {
+        project_id: Uint64::new(1),
+        holder_alloc: Uint128::new(80),
+        tier_weights: None,
+        lottery: None,
+        whitelist_period: None,
+        fundraising_period: None,
+        public_sale: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+
+    let register = ExecuteMsg::RegisterWhitelist {
+        project_id: Uint64::new(1),
+    };
+    //no stake, no whitelist
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("backer1", &[]),
+        register.clone(),
+    );
+    assert!(res.is_err());
+
+    deps.querier.with_card_type("backer1", CardType::Platium);
+    deps.querier.with_card_type("backer2", CardType::Gold);
+    for wallet in ["backer1", "backer2"] {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(wallet, &[]),
+            register.clone(),
+        )
+        .unwrap();
+    }
+
+    //backer1 unstaked down to bronze, backer2 unstaked all
+    deps.querier.with_card_type("backer1", CardType::Bronze);
+    deps.querier.with_card_type("backer2", CardType::Other);
+    //recheck goes on in batches, close does the rest
+    let msg = ExecuteMsg::RecheckWhitelist {
+        project_id: Uint64::new(1),
+        limit: Some(1),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("anyone", &[]), msg).unwrap();
+    assert_eq!(res.attributes[1].value, "false");
+    let msg = ExecuteMsg::CloseWhitelist {
+        project_id: Uint64::new(1),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+
+    let msg = QueryMsg::GetWhitelist {
+        project_id: Uint64::new(1),
+        start_after: None,
+        limit: None,
+    };
+    let whitelist: Vec<WhitelistState> =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(whitelist.len(), 1);
+    assert_eq!(whitelist[0].card_type, CardType::Bronze);
+    assert_eq!(whitelist[0].allocation, Uint128::new(240_000_000));
+}
+
+#[test]
+fn lottery_draw() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        admin: Some(String::from("admin")),
+        wefund: Some(String::from("wefund")),
+        denom: Some("ujunox".to_string()),
+        decimals: Some(Uint64::new(6)),
+        vesting_contract: None,
+        staking_contract: Some(String::from("staking")),
+        fee_bps: None,
+        fee_recipients: None,
+        bond_amount: None,
+        bond_token: None,
+        bond_token_amount: None,
+        bond_slash_bps: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let msg = ExecuteMsg::AddCommunitymember {
+        wallet: String::from("community1"),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let msg = ExecuteMsg::SubmitProject {
+        project_info: project_info("creator1", 300),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+    approve(&mut deps, 1, None);
+
+    //one platium slot for three platium holders
+    let msg = ExecuteMsg::OpenWhitelist {
+        project_id: Uint64::new(1),
+        holder_alloc: Uint128::new(80),
+        tier_weights: None,
+        lottery: Some(LotterySlots {
+            platium: 1,
+            gold: 0,
+            silver: 0,
+            bronze: 0,
+        }),
+        whitelist_period: None,
+        fundraising_period: None,
+        public_sale: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+    let register = ExecuteMsg::RegisterWhitelist {
+        project_id: Uint64::new(1),
+    };
+    for wallet in ["backer1", "backer2", "backer3"] {
+        deps.querier.with_card_type(wallet, CardType::Platium);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(wallet, &[]),
+            register.clone(),
+        )
+        .unwrap();
+    }
+
+    for (wallet, secret) in [("creator1", "creator secret"), ("admin", "admin secret")] {
+        let msg = ExecuteMsg::CommitLotterySeed {
+            project_id: Uint64::new(1),
+            commitment: hex::encode(Sha256::digest(secret.as_bytes())),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(wallet, &[]), msg).unwrap();
+    }
+    let reveal = |secret: &str| ExecuteMsg::RevealLotterySeed {
+        project_id: Uint64::new(1),
+        secret: Binary::from(secret.as_bytes()),
+    };
+    //admin reveals first, each only its own secret
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator1", &[]),
+        reveal("creator secret"),
+    );
+    assert!(res.is_err());
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        reveal("creator secret"),
+    );
+    assert!(res.is_err());
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("admin", &[]),
+        reveal("admin secret"),
+    )
+    .unwrap();
+
+    //no registering after a secret is out, no draw before both are out
+    deps.querier.with_card_type("backer4", CardType::Platium);
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("backer4", &[]),
+        register,
+    );
+    assert!(res.is_err());
+    let close = ExecuteMsg::CloseWhitelist {
+        project_id: Uint64::new(1),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator1", &[]),
+        close.clone(),
+    );
+    assert!(res.is_err());
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("creator1", &[]),
+        reveal("creator secret"),
+    )
+    .unwrap();
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), close).unwrap();
+
+    let msg = QueryMsg::GetLottery {
+        project_id: Uint64::new(1),
+    };
+    let lottery: Option<LotteryState> =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    let lottery = lottery.unwrap();
+    let seed = Sha256::new()
+        .chain("creator secret")
+        .chain("admin secret")
+        .finalize();
+    assert_eq!(lottery.seed, hex::encode(seed));
+    assert_eq!(lottery.winners.len(), 1);
+
+    //winner takes the whole holder pool
+    let msg = QueryMsg::GetWhitelist {
+        project_id: Uint64::new(1),
+        start_after: None,
+        limit: None,
+    };
+    let whitelist: Vec<WhitelistState> =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(whitelist.len(), 1);
+    assert_eq!(whitelist[0].wallet, lottery.winners[0]);
+    assert_eq!(whitelist[0].allocation, Uint128::new(240_000_000));
+
+    //creator withholding its secret past the deadline gets the project rejected
+    let msg = ExecuteMsg::SubmitProject {
+        project_info: project_info("creator1", 300),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+    approve(&mut deps, 2, None);
+    let msg = ExecuteMsg::OpenWhitelist {
+        project_id: Uint64::new(2),
+        holder_alloc: Uint128::new(80),
+        tier_weights: None,
+        lottery: Some(LotterySlots {
+            platium: 1,
+            gold: 0,
+            silver: 0,
+            bronze: 0,
+        }),
+        whitelist_period: None,
+        fundraising_period: None,
+        public_sale: None,
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+    for (wallet, secret) in [("creator1", "creator secret"), ("admin", "admin secret")] {
+        let msg = ExecuteMsg::CommitLotterySeed {
+            project_id: Uint64::new(2),
+            commitment: hex::encode(Sha256::digest(secret.as_bytes())),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(wallet, &[]), msg).unwrap();
+    }
+    let msg = ExecuteMsg::RevealLotterySeed {
+        project_id: Uint64::new(2),
+        secret: Binary::from("admin secret".as_bytes()),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let close = ExecuteMsg::CloseWhitelist {
+        project_id: Uint64::new(2),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        close.clone(),
+    );
+    assert!(res.is_err());
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(24 * 60 * 60);
+    execute(deps.as_mut(), env, mock_info("anyone", &[]), close).unwrap();
+    let x: ProjectState = from_binary(
+        &query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetProject {
+                project_id: Uint64::new(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(x.project_status, ProjectStatus::Rejected);
+    assert_eq!(x.rejected_reason, "lottery seed not revealed");
+}
+
+#[test]
+fn public_sale() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        admin: Some(String::from("admin")),
+        wefund: Some(String::from("wefund")),
+        denom: Some("ujunox".to_string()),
+        decimals: Some(Uint64::new(6)),
+        vesting_contract: None,
+        staking_contract: Some(String::from("staking")),
+        fee_bps: None,
+        fee_recipients: None,
+        bond_amount: None,
+        bond_token: None,
+        bond_token_amount: None,
+        bond_slash_bps: None,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let msg = ExecuteMsg::AddCommunitymember {
+        wallet: String::from("community1"),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let msg = ExecuteMsg::SubmitProject {
+        project_info: project_info("creator1", 300),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+    approve(&mut deps, 1, None);
+
+    let guaranteed_end = mock_env().block.time.seconds() + 100;
+    let msg = ExecuteMsg::OpenWhitelist {
+        project_id: Uint64::new(1),
+        holder_alloc: Uint128::new(80),
+        tier_weights: None,
+        lottery: None,
+        whitelist_period: None,
+        fundraising_period: None,
+        public_sale: Some(PublicSale {
+            guaranteed_end: Uint64::new(guaranteed_end),
+            wallet_cap: Uint128::new(50_000_000),
+        }),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+    deps.querier.with_card_type("backer1", CardType::Platium);
+    let msg = ExecuteMsg::RegisterWhitelist {
+        project_id: Uint64::new(1),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("backer1", &[]), msg).unwrap();
+    let msg = ExecuteMsg::CloseWhitelist {
+        project_id: Uint64::new(1),
+    };
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+
+    //only whitelisted wallets back in guaranteed window
+    assert!(back(&mut deps, "anyone", &[Coin::new(10_000_000, "ujunox")]).is_err());
+    let open = ExecuteMsg::OpenPublicSale {
+        project_id: Uint64::new(1),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        open.clone(),
+    );
+    assert!(res.is_err());
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(100);
+    execute(deps.as_mut(), env, mock_info("anyone", &[]), open).unwrap();
+    let msg = QueryMsg::GetProject {
+        project_id: Uint64::new(1),
+    };
+    let project: ProjectState =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(project.project_status, ProjectStatus::PublicSale);
+    assert_eq!(project.public_start, Uint64::new(guaranteed_end));
+
+    //any wallet backs up to the cap, 5% fee goes to wefund
+    back(&mut deps, "anyone", &[Coin::new(50_000_000, "ujunox")]).unwrap();
+    assert!(back(&mut deps, "anyone", &[Coin::new(10_000_000, "ujunox")]).is_err());
+    let msg = QueryMsg::GetAllocation {
+        project_id: Uint64::new(1),
+        wallet: String::from("anyone"),
+    };
+    let allocation: AllocationResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(allocation.backed, Uint128::new(47_500_000));
+    assert_eq!(allocation.remaining, Uint128::new(2_500_000));
+}
+
+#[test]
+fn merkle_whitelist() {
+    let mut deps = mock_dependencies(&[]);
+    setup_fundraising(&mut deps, None);
+
+    let entries = [
+        ("backer1", CardType::Platium, 100_000_000u128),
+        ("backer2", CardType::Gold, 50_000_000),
+        ("backer3", CardType::Bronze, 10_000_000),
+    ];
+    let leaves: Vec<Binary> = entries
+        .iter()
+        .map(|(wallet, card_type, allocation)| {
+            leaf_hash(wallet, card_type, Uint128::new(*allocation))
+        })
+        .collect();
+    let root = merkle_root(&leaves);
+
+    //creator or admin posts the root
+    let msg = ExecuteMsg::SetMerkleRoot {
+        project_id: Uint64::new(1),
+        root: root.clone(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone", &[]),
+        msg.clone(),
+    );
+    assert!(res.is_err());
+    execute(deps.as_mut(), mock_env(), mock_info("creator1", &[]), msg).unwrap();
+
+    let back_with_proof = |allocation: u128, amount: u128| {
+        let msg = ExecuteMsg::Back2Project {
+            project_id: Uint64::new(1),
+            backer_wallet: String::from("backer2"),
+            otherchain: "".to_string(),
+            otherchain_wallet: "".to_string(),
+            whitelist_proof: Some(WhitelistProof {
+                card_type: CardType::Gold,
+                allocation: Uint128::new(allocation),
+                proof: merkle_proof(&leaves, 1),
+            }),
+            fundraising_stage: Uint128::zero(),
+            token_amount: Uint128::new(10),
+        };
+        let info = mock_info("backer2", &[Coin::new(amount, "ujunox")]);
+        (info, msg)
+    };
+
+    //claimed allocation must match the leaf
+    let (info, msg) = back_with_proof(500_000_000, 50_000_000);
+    assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
+    assert!(back(&mut deps, "backer3", &[Coin::new(10_000_000, "ujunox")]).is_err());
+
+    //5% fee goes to wefund, the larger allocation caps both entries together
+    let (info, msg) = back_with_proof(50_000_000, 50_000_000);
+    execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert!(back(&mut deps, "backer2", &[Coin::new(30_000_000, "ujunox")]).is_err());
+    back(&mut deps, "backer2", &[Coin::new(20_000_000, "ujunox")]).unwrap();
+    let (info, msg) = back_with_proof(50_000_000, 5_000_000);
+    assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
+
+    let msg = QueryMsg::GetAllocation {
+        project_id: Uint64::new(1),
+        wallet: String::from("backer2"),
+    };
+    let res: AllocationResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(res.allocation, Uint128::new(70_588_200));
+    assert_eq!(res.backed, Uint128::new(66_500_000));
+
+    let msg = QueryMsg::GetProject {
+        project_id: Uint64::new(1),
+    };
+    let project: ProjectState =
+        from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+    assert_eq!(project.backerbacked_amount, Uint128::new(66_500_000));
+    assert_eq!(project.milestone_total_weight, Uint128::new(66_500_000));
+}
+
+#[test]
+fn typed_events() {
+    let mut deps = mock_dependencies(&[]);
+    setup_fundraising(&mut deps, None);
+    let res = back(&mut deps, "backer1", &[Coin::new(100_000_000, "ujunox")]).unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new(EVENT_BACKING)
+            .add_attribute(ATTR_PROJECT_ID, "1")
+            .add_attribute(ATTR_WALLET, "backer1")
+            .add_attribute(ATTR_TOKEN_ID, "1")
+            .add_attribute(ATTR_AMOUNT, "95000000ujunox")
+            .add_attribute(ATTR_BACKED, "95000000")
+            .add_attribute(ATTR_STAGE, "0")
+            .add_attribute(ATTR_TOKEN_AMOUNT, "10")]
+    );
+
+    //passing vote releases the last milestone and finishes the project
+    let mut deps = mock_dependencies(&[]);
+    let env = setup_releasing(&mut deps);
+    let res = vote(&mut deps, &env, "backer1", VoteOption::Yes).unwrap();
+    assert_eq!(
+        res.events,
+        vec![
+            Event::new(EVENT_MILESTONE_RELEASED)
+                .add_attribute(ATTR_PROJECT_ID, "1")
+                .add_attribute(ATTR_STEP, "0")
+                .add_attribute(ATTR_AMOUNT, "300000000"),
+            Event::new(EVENT_PROJECT_STATUS)
+                .add_attribute(ATTR_PROJECT_ID, "1")
+                .add_attribute(ATTR_OLD_STATUS, "Releasing")
+                .add_attribute(ATTR_NEW_STATUS, "Done"),
+            Event::new(EVENT_VOTE_CAST)
+                .add_attribute(ATTR_PROJECT_ID, "1")
+                .add_attribute(ATTR_KIND, "milestone")
+                .add_attribute(ATTR_WALLET, "backer1")
+                .add_attribute(ATTR_OPTION, "Yes")
+                .add_attribute(ATTR_WEIGHT, "95000000")
+                .add_attribute(ATTR_ROUND, "1"),
+        ]
+    );
+}
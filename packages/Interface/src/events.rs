@@ -0,0 +1,52 @@
+//------------event types, stable for indexers------------------------------
+pub const EVENT_PROJECT_CREATED: &str = "project_created";
+pub const EVENT_PROJECT_UPDATED: &str = "project_updated";
+pub const EVENT_PROJECT_STATUS: &str = "project_status";
+pub const EVENT_BACKING: &str = "backing";
+pub const EVENT_POSITION_TRANSFERRED: &str = "position_transferred";
+pub const EVENT_REFUND_CLAIMED: &str = "refund_claimed";
+pub const EVENT_WHITELIST_REGISTERED: &str = "whitelist_registered";
+pub const EVENT_ALLOCATION: &str = "allocation";
+pub const EVENT_VOTE_CAST: &str = "vote_cast";
+pub const EVENT_MILESTONE_RELEASED: &str = "milestone_released";
+pub const EVENT_LOTTERY_DRAWN: &str = "lottery_drawn";
+
+pub const EVENT_VESTING_USER_ADDED: &str = "vesting_user_added";
+pub const EVENT_VESTING_USER_TRANSFERRED: &str = "vesting_user_transferred";
+pub const EVENT_VESTING_CLAIM: &str = "vesting_claim";
+
+pub const EVENT_STAKE: &str = "stake";
+pub const EVENT_UNSTAKE: &str = "unstake";
+pub const EVENT_REWARD_CLAIM: &str = "reward_claim";
+pub const EVENT_TIER_CHANGE: &str = "tier_change";
+
+//------------attribute keys shared by all events---------------------------
+pub const ATTR_PROJECT_ID: &str = "project_id";
+pub const ATTR_WALLET: &str = "wallet";
+pub const ATTR_RECIPIENT: &str = "recipient";
+pub const ATTR_AMOUNT: &str = "amount";
+pub const ATTR_OLD_STATUS: &str = "old_status";
+pub const ATTR_NEW_STATUS: &str = "new_status";
+pub const ATTR_CREATOR: &str = "creator";
+pub const ATTR_REVISION: &str = "revision";
+pub const ATTR_TOKEN_ID: &str = "token_id";
+pub const ATTR_BACKED: &str = "backed";
+pub const ATTR_STAGE: &str = "stage";
+pub const ATTR_TOKEN_AMOUNT: &str = "token_amount";
+pub const ATTR_CARD_TYPE: &str = "card_type";
+pub const ATTR_HOLDER_TICKET: &str = "holder_ticket";
+pub const ATTR_COMMUNITY_TICKET: &str = "community_ticket";
+pub const ATTR_KIND: &str = "kind";
+pub const ATTR_OPTION: &str = "option";
+pub const ATTR_WEIGHT: &str = "weight";
+pub const ATTR_ROUND: &str = "round";
+pub const ATTR_STEP: &str = "step";
+pub const ATTR_OLD_TIER: &str = "old_tier";
+pub const ATTR_NEW_TIER: &str = "new_tier";
+pub const ATTR_CARD_NUMBER: &str = "card_number";
+pub const ATTR_SEED: &str = "seed";
+pub const ATTR_WINNERS: &str = "winners";
+
+//------------values of ATTR_KIND on vote_cast------------------------------
+pub const VOTE_KIND_MILESTONE: &str = "milestone";
+pub const VOTE_KIND_APPROVAL: &str = "approval";
@@ -1,3 +1,10 @@
+pub mod cw721;
+pub mod events;
+pub mod merkle;
 pub mod staking;
+pub mod version;
 pub mod vesting;
-pub mod wefund;
\ No newline at end of file
+pub mod wefund;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,67 @@
+use crate::staking::CardType;
+use cosmwasm_std::{Binary, Uint128};
+use sha2::{Digest, Sha256};
+
+//------------leaf of merkle whitelist: (wallet, tier, allocation)-----------
+//------------0x00 || wallet || 0x00 || tier index || allocation as 16 bytes big endian
+pub fn leaf_hash(wallet: &str, card_type: &CardType, allocation: Uint128) -> Binary {
+    let hash = Sha256::new()
+        .chain([0u8])
+        .chain(wallet.as_bytes())
+        .chain([0u8])
+        .chain([card_type.clone() as u8])
+        .chain(allocation.u128().to_be_bytes())
+        .finalize();
+    Binary::from(hash.as_slice())
+}
+
+//------------0x01 || lower || higher, proofs need no left/right flags-------
+pub(crate) fn node_hash(a: &[u8], b: &[u8]) -> Binary {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    let hash = Sha256::new().chain([1u8]).chain(low).chain(high).finalize();
+    Binary::from(hash.as_slice())
+}
+
+//------------odd node of a level moves up unchanged-------------------------
+fn next_level(level: &[Binary]) -> Vec<Binary> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [a, b] => node_hash(a, b),
+            _ => pair[0].clone(),
+        })
+        .collect()
+}
+
+pub fn merkle_root(leaves: &[Binary]) -> Binary {
+    if leaves.is_empty() {
+        return Binary::from(Sha256::digest(&[]).as_slice());
+    }
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level.remove(0)
+}
+
+pub fn merkle_proof(leaves: &[Binary], index: usize) -> Vec<Binary> {
+    let mut proof = Vec::new();
+    let mut level = leaves.to_vec();
+    let mut index = index;
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if sibling < level.len() {
+            proof.push(level[sibling].clone());
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    proof
+}
+
+pub fn verify_proof(root: &Binary, leaf: &Binary, proof: &[Binary]) -> bool {
+    let hash = proof
+        .iter()
+        .fold(leaf.clone(), |hash, sibling| node_hash(&hash, sibling));
+    hash == *root
+}
@@ -0,0 +1,111 @@
+use cosmwasm_std::{Binary, Uint128};
+use sha2::{Digest, Sha256};
+
+use crate::merkle::{leaf_hash, merkle_proof, merkle_root, node_hash, verify_proof};
+use crate::staking::CardType;
+
+fn leaves() -> Vec<Binary> {
+    [
+        ("backer1", CardType::Platium, 100u128),
+        ("backer2", CardType::Gold, 50),
+        ("backer3", CardType::Silver, 30),
+        ("backer4", CardType::Bronze, 20),
+        ("backer5", CardType::Other, 10),
+    ]
+    .iter()
+    .map(|(wallet, card_type, allocation)| leaf_hash(wallet, card_type, Uint128::new(*allocation)))
+    .collect()
+}
+
+#[test]
+fn leaf_and_node_hash() {
+    let leaf = leaf_hash("backer1", &CardType::Gold, Uint128::new(100));
+    let mut preimage = vec![0u8];
+    preimage.extend_from_slice(b"backer1");
+    preimage.push(0);
+    preimage.push(CardType::Gold as u8);
+    preimage.extend_from_slice(&100u128.to_be_bytes());
+    assert_eq!(leaf.as_slice(), Sha256::digest(&preimage).as_slice());
+
+    //tier and allocation are part of the leaf
+    assert_ne!(
+        leaf,
+        leaf_hash("backer1", &CardType::Silver, Uint128::new(100))
+    );
+    assert_ne!(
+        leaf,
+        leaf_hash("backer1", &CardType::Gold, Uint128::new(101))
+    );
+
+    //pair is sorted, so both orders hash the same
+    let other = leaf_hash("backer2", &CardType::Gold, Uint128::new(100));
+    let (low, high) = if leaf.as_slice() <= other.as_slice() {
+        (&leaf, &other)
+    } else {
+        (&other, &leaf)
+    };
+    let mut preimage = vec![1u8];
+    preimage.extend_from_slice(low);
+    preimage.extend_from_slice(high);
+    let node = node_hash(&leaf, &other);
+    assert_eq!(node.as_slice(), Sha256::digest(&preimage).as_slice());
+    assert_eq!(node, node_hash(&other, &leaf));
+}
+
+#[test]
+fn proof_round_trip() {
+    let leaves = leaves();
+    let root = merkle_root(&leaves);
+    for (index, leaf) in leaves.iter().enumerate() {
+        let proof = merkle_proof(&leaves, index);
+        assert!(verify_proof(&root, leaf, &proof));
+    }
+
+    //single leaf is its own root
+    let single = vec![leaves[0].clone()];
+    assert_eq!(merkle_root(&single), leaves[0]);
+    assert!(verify_proof(&merkle_root(&single), &leaves[0], &[]));
+}
+
+#[test]
+fn wrong_proof() {
+    let leaves = leaves();
+    let root = merkle_root(&leaves);
+
+    //proof of another leaf
+    assert!(!verify_proof(&root, &leaves[0], &merkle_proof(&leaves, 2)));
+
+    //leaf claiming a larger allocation
+    let forged = leaf_hash("backer1", &CardType::Platium, Uint128::new(1000));
+    assert!(!verify_proof(&root, &forged, &merkle_proof(&leaves, 0)));
+
+    //tampered or missing sibling
+    let mut proof = merkle_proof(&leaves, 0);
+    proof[0] = leaves[4].clone();
+    assert!(!verify_proof(&root, &leaves[0], &proof));
+    let mut proof = merkle_proof(&leaves, 0);
+    proof.pop();
+    assert!(!verify_proof(&root, &leaves[0], &proof));
+}
+
+#[test]
+fn wrong_order() {
+    let leaves = leaves();
+    let root = merkle_root(&leaves);
+
+    //siblings must be applied from leaf up to root
+    let mut proof = merkle_proof(&leaves, 0);
+    assert!(proof.len() > 1);
+    proof.reverse();
+    assert!(!verify_proof(&root, &leaves[0], &proof));
+
+    //leaves in another order build another root
+    let mut shuffled = leaves.clone();
+    shuffled.swap(0, 2);
+    assert_ne!(merkle_root(&shuffled), root);
+    assert!(!verify_proof(
+        &root,
+        &leaves[0],
+        &merkle_proof(&shuffled, 2)
+    ));
+}
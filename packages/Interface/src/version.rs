@@ -0,0 +1,12 @@
+use cosmwasm_std::{StdError, StdResult};
+
+//------------numeric parts of "major.minor.patch", compare like semver------
+pub fn parse_version(version: &str) -> StdResult<Vec<u64>> {
+    version
+        .split('.')
+        .map(|x| {
+            x.parse::<u64>()
+                .map_err(|_| StdError::generic_err(format!("Invalid version: {}", version)))
+        })
+        .collect()
+}
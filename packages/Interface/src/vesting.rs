@@ -7,6 +7,9 @@ pub struct InstantiateMsg {
     pub admin: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
@@ -44,6 +47,13 @@ pub enum ExecuteMsg {
         wallet: Addr,
         amount: Uint128,
     },
+    TransferUser {
+        project_id: Uint64,
+        stage: Uint128,
+        from: Addr,
+        to: Addr,
+        amount: Uint128,
+    },
     SetUsers {
         project_id: Uint64,
         stage: Uint128,
@@ -57,11 +67,30 @@ pub enum ExecuteMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    GetConfig { project_id: Uint64 },
-    GetPendingTokens { project_id: Uint64, wallet: String },
-    GetUserInfo { project_id: Uint64, wallet: String },
-    GetBalance { project_id: Uint64, wallet: String },
-    GetProjectInfo { project_id: Uint64 },
+    GetConfig {
+        project_id: Uint64,
+    },
+    GetPendingTokens {
+        project_id: Uint64,
+        wallet: String,
+    },
+    GetUserInfo {
+        project_id: Uint64,
+        wallet: String,
+    },
+    GetBalance {
+        project_id: Uint64,
+        wallet: String,
+    },
+    GetProjectInfo {
+        project_id: Uint64,
+    },
+    GetUsers {
+        project_id: Uint64,
+        stage: Uint128,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
     GetAllProjectInfo {},
     GetOwner {},
 }
@@ -96,6 +125,5 @@ pub struct ProjectInfo {
     pub project_id: Uint64,
     pub config: Config,
     pub vest_param: Vec<VestingParameter>,
-    pub users: Vec<Vec<UserInfo>>,
-    pub total: Vec<Uint128>,
+    pub total: Vec<Uint128>, //per stage, users are stored apart
 }
@@ -1,5 +1,7 @@
+use crate::cw721::Approval;
 use crate::staking::CardType;
-use cosmwasm_std::{Addr, Coin, Uint128, Uint64};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, Uint128, Uint64};
+use cw20::{Cw20ReceiveMsg, Expiration};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +13,81 @@ pub struct Config {
     pub denom: String,
     pub decimals: u32,
     pub vesting_contract: Addr,
+    pub staking_contract: Addr, //card tiers for whitelist, empty: not set
+    pub fee_bps: u64,           //platform fee in basis points
+    pub fee_recipients: Vec<FeeRecipient>, //empty: all fee to wefund
+    pub bond_amount: Uint128,   //submit bond in denom, zero: not accepted
+    pub bond_token: Addr,       //WFD cw20 for submit bond, empty: not accepted
+    pub bond_token_amount: Uint128, //submit bond in bond token
+    pub bond_slash_bps: u64,    //part of bond to wefund on removal
+    pub approval_quorum: Decimal, //share of community that must vote
+    pub approval_threshold: Decimal, //share of yes in yes + no to pass
+    pub approval_vote_period: Uint64, //seconds
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeRecipient {
+    pub address: String,
+    pub weight: Uint64,
+}
+
+//-------------accepted denoms------------------------------------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AcceptedDenom {
+    pub denom: String, //native denom or cw20 token address
+    pub is_cw20: bool,
+    pub rate: Decimal, //amount in project target unit for one unit of denom
+}
+
+//-------------submission bond of each project-----------------------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BondStatus {
+    Held,
+    Returned,
+    Slashed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BondState {
+    pub wallet: Addr,
+    pub amount: Coin, //denom is token address for cw20
+    pub is_cw20: bool,
+    pub status: BondStatus,
+    pub slashed: Uint128,
+}
+
+//-------------backer position, a cw721 token of this contract-----------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Position {
+    pub token_id: Uint64,
+    pub owner: Addr,
+    pub project_id: Uint64,
+    pub stage: Uint128,
+    pub amount: Coin,          //escrowed fund after fee
+    pub backed: Uint128,       //in config denom unit
+    pub token_amount: Uint128, //project token allocation, vested to holder
+    pub weight: Uint128,       //milestone vote weight, the backed amount
+    pub voted_round: Uint64,   //round in which holder's vote counts this weight
+    pub refunded: bool,
+    pub crosschain: bool, //attested backing, nothing escrowed here to refund
+    pub approvals: Vec<Approval>, //spenders allowed to transfer, cleared on transfer
+}
+
+//-------------backing on other chain, signed by relayer------------------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Attestation {
+    pub project_id: Uint64,
+    pub backer_wallet: String,
+    pub denom: String,
+    pub amount: Uint128,
+    pub fundraising_stage: Uint128,
+    pub token_amount: Uint128,
+    pub otherchain: String,
+    pub otherchain_wallet: String,
+    pub source_tx_hash: String,
+    pub chain_id: String, //chain and contract the signature is bound to
+    pub contract: String,
 }
 
 //-------------backer states---------------------------------
@@ -25,7 +102,32 @@ pub struct BackerState {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Vote {
     pub wallet: Addr,
-    pub voted: bool,
+    pub weight: Uint128, //backed amount in config denom unit
+    pub option: VoteOption,
+    pub round: Uint64, //counts only in current milestone_vote_round
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteOption {
+    Yes,
+    No,
+    Abstain,
+}
+
+//--------------live result of the current milestone vote-------------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MilestoneTallyResponse {
+    pub milestone_step: Uint128,
+    pub yes: Uint128,
+    pub no: Uint128,
+    pub abstain: Uint128,
+    pub total_weight: Uint128,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub quorum_reached: bool,
+    pub passed: bool,
+    pub vote_end: Uint64,
 }
 
 //--------------Milestone---------------------------------------
@@ -38,8 +140,7 @@ pub struct Milestone {
     pub milestone_startdate: String,
     pub milestone_enddate: String,
     pub milestone_amount: Uint128,
-    pub milestone_status: Uint128, //0:voting, 1:releasing 2:released
-    pub milestone_votes: Vec<Vote>,
+    pub milestone_status: Uint128, //0:voting, 1:releasing 2:released 3:rejected
 }
 //------------Team Description-------------------------------
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -68,6 +169,71 @@ pub struct WhitelistState {
     pub backed: Uint128,
 }
 
+//------------roles granted by admin, owner holds all roles-------------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Admin,            //all roles
+    Operator,         //fundraising stage, milestone release
+    CommunityManager, //community members
+    Relayer,          //backing from other chains
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoleInfo {
+    pub wallet: Addr,
+    pub roles: Vec<Role>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllocationResponse {
+    pub allocation: Uint128,
+    pub backed: Uint128,
+    pub remaining: Uint128,
+}
+
+//--------------ticket weight of each card tier-----------------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TierWeights {
+    pub platium: Uint128,
+    pub gold: Uint128,
+    pub silver: Uint128,
+    pub bronze: Uint128,
+}
+
+//--------------winning slots of each card tier in lottery mode----------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LotterySlots {
+    pub platium: u32,
+    pub gold: u32,
+    pub silver: u32,
+    pub bronze: u32,
+}
+
+//--------------seed by commit-reveal of creator and admin, drawn on close---
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LotteryState {
+    pub slots: LotterySlots,
+    pub creator_commit: String, //hex sha256 of secret, empty: not committed
+    pub admin_commit: String,
+    pub creator_secret: Option<Binary>,
+    pub admin_secret: Option<Binary>,
+    pub seed: String, //hex sha256 of both secrets, set on draw
+    pub winners: Vec<Addr>,
+    pub reveal_end: Uint64, //secrets due by then, set on second commit
+}
+
+//--------------allocation math of close whitelist, run on current state-----
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllocationPreviewResponse {
+    pub holder_pool: Uint128,
+    pub community_pool: Uint128,
+    pub whitelist_weight: Uint128,
+    pub community_count: Uint128,
+    pub holder_ticket: Uint128,
+    pub community_ticket: Uint128,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub enum ProjectStatus {
     WefundVote,
@@ -76,7 +242,80 @@ pub enum ProjectStatus {
     Releasing,
     Done,
     Fail,
+    Rejected,
+    PublicSale,
+}
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovalTallyResponse {
+    pub yes: Uint64,
+    pub no: Uint64,
+    pub abstain: Uint64,
+    pub total: Uint64,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub quorum_reached: bool,
+    pub passed: bool,
+    pub vote_end: Uint64,
+}
+
+//------------ project query filter, unset fields match all-------------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ProjectFilter {
+    pub project_status: Option<ProjectStatus>,
+    pub creator_wallet: Option<String>,
+    pub project_ecosystem: Option<String>,
+    pub project_fundtype: Option<String>,
+}
+
+//------------ project state without backer, whitelist and vote vectors------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProjectSummary {
+    pub project_id: Uint64,
+    pub project_company: String,
+    pub project_title: String,
+    pub project_ecosystem: String,
+    pub project_fundtype: String,
+    pub project_logo: String,
+    pub creator_wallet: Addr,
+    pub project_collected: Uint128,
+    pub project_softcap: Uint128,
+    pub project_status: ProjectStatus,
+    pub backerbacked_amount: Uint128,
+    pub backer_count: Uint64,
+    pub milestone_count: Uint64,
+    pub project_milestonestep: Uint128,
+    pub fundraising_period: Option<Period>,
+}
+
+//------------ project fields set by creator on submit and update------------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProjectInfo {
+    pub project_company: String,
+    pub project_title: String,
+    pub project_description: String,
+    pub project_ecosystem: String,
+    pub project_fundtype: String,
+    pub project_createddate: String,
+    pub project_saft: String,
+    pub project_logo: String,
+    pub project_whitepaper: String,
+    pub project_website: String,
+    pub project_email: String,
+    pub creator_wallet: String,
+    pub project_collected: Uint128,
+    pub project_softcap: Option<Uint128>,
+    pub project_milestones: Vec<Milestone>,
+    pub project_teammembers: Vec<TeamMember>,
+    pub vesting: Vec<VestingParameter>,
+    pub token_addr: String,
+
+    pub country: String,
+    pub cofounder_name: String,
+    pub service_wefund: String,
+    pub service_charity: String,
+    pub professional_link: String,
 }
+
 //------------ project state--------------------------------
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ProjectState {
@@ -100,31 +339,87 @@ pub struct ProjectState {
     pub professional_link: String,
     //------------------------------------------------------------------------------
     pub project_id: Uint64,
+    pub revision: Uint64,      //counts updates by creator
+    pub previous_hash: String, //sha256 of previous revision, empty on first
     pub creator_wallet: Addr,
     pub project_collected: Uint128,
+    pub project_softcap: Uint128, //released on finalize if reached
 
     //0:wefund voting 1:fundrasing 2:releasing 3:done 4:fail
     pub project_status: ProjectStatus,
     pub fundraising_stage: Uint128,
+    pub fee_bps: Option<Uint64>, //overrides config fee_bps
 
+    //---------community approval, votes are stored apart------------------
+    pub approval_vote_end: Uint64, //zero until proposed
+    pub approval_total: Uint64,    //community members when proposed
+    pub approval_yes: Uint64,
+    pub approval_no: Uint64,
+    pub approval_abstain: Uint64,
+    pub rejected_reason: String,
+
+    //---------backed amount in config denom unit and escrowed funds per denom----
     pub backerbacked_amount: Uint128,
-    //---------backer states for 50% of collected------------------------
-    pub backer_states: Vec<BackerState>,
+    pub backerbacked_funds: Vec<Coin>,
+    pub backerbacked_total_funds: Vec<Coin>, //all backed funds, for refund share
+    pub crosschain_backed_amount: Uint128,   //attested, held on other chains: target and votes only
+    pub backer_count: Uint64,
 
-    //----------milestone states-----------------------------------------
-    pub milestone_states: Vec<Milestone>,
+    //----------milestone states, milestones and votes are stored apart----------
+    pub milestone_count: Uint64,
     pub project_milestonestep: Uint128,
+    pub milestone_quorum: Decimal, //share of total weight that must vote
+    pub milestone_threshold: Decimal, //share of yes in yes + no to pass
+    pub milestone_vote_period: Uint64, //seconds
+    pub milestone_vote_end: Uint64,
+    pub milestone_max_rejections: Uint64, //fail project on reaching
+    pub milestone_rejections: Uint64,
+    //---------running tally of current vote round-----------------------
+    pub milestone_vote_round: Uint64,
+    pub milestone_total_weight: Uint128,
+    pub milestone_yes: Uint128,
+    pub milestone_no: Uint128,
+    pub milestone_abstain: Uint128,
     //---------team members-----------------------------------------------
     pub teammember_states: Vec<TeamMember>,
     //---------vesting-----------------------------------------------
     pub vesting: Vec<VestingParameter>,
 
     pub token_addr: Addr,
-    //---------whitelist-----------------------------
-    pub whitelist: Vec<WhitelistState>,
-    pub holder_alloc: Uint128,
+    //---------whitelist, entries are stored apart-------------------------
+    pub whitelist_weight: Uint128, //sum of card weights of registered holders
+    pub tier_weights: TierWeights,
+    pub holder_alloc: Uint128, //percent of unfilled target for holders, rest to community
     pub holder_ticket: Uint128,
     pub community_ticket: Uint128,
+    //---------phase bounds checked against block time-----------------
+    pub whitelist_period: Option<Period>,
+    pub fundraising_period: Option<Period>,
+    //---------public round after guaranteed allocations-----------------
+    pub public_sale: Option<PublicSale>,
+    pub public_start: Uint64, //set on entering public sale
+}
+
+//---------[start, end) in seconds-----------------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Period {
+    pub start: Uint64,
+    pub end: Uint64,
+}
+
+//---------leaf of merkle whitelist with path to posted root, see Interface::merkle---
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WhitelistProof {
+    pub card_type: CardType,
+    pub allocation: Uint128, //in config denom unit
+    pub proof: Vec<Binary>,
+}
+
+//---------first-come-first-served round for allocations left unused---------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PublicSale {
+    pub guaranteed_end: Uint64, //seconds, whitelist allocations hold until
+    pub wallet_cap: Uint128,    //per wallet, in config denom unit
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -134,8 +429,18 @@ pub struct InstantiateMsg {
     pub denom: Option<String>,
     pub decimals: Option<Uint64>,
     pub vesting_contract: Option<String>,
+    pub staking_contract: Option<String>,
+    pub fee_bps: Option<Uint64>,
+    pub fee_recipients: Option<Vec<FeeRecipient>>,
+    pub bond_amount: Option<Uint128>,
+    pub bond_token: Option<String>,
+    pub bond_token_amount: Option<Uint128>,
+    pub bond_slash_bps: Option<Uint64>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
@@ -145,45 +450,38 @@ pub enum ExecuteMsg {
         denom: Option<String>,
         decimals: Option<Uint64>,
         vesting_contract: Option<String>,
+        staking_contract: Option<String>,
+        fee_bps: Option<Uint64>,
+        fee_recipients: Option<Vec<FeeRecipient>>,
+        bond_amount: Option<Uint128>,
+        bond_token: Option<String>,
+        bond_token_amount: Option<Uint128>,
+        bond_slash_bps: Option<Uint64>,
     },
-    AddProject {
-        project_id: Uint64,
-        project_company: String,
-        project_title: String,
-        project_description: String,
-        project_ecosystem: String,
-        project_fundtype: String,
-        project_createddate: String,
-        project_saft: String,
-        project_logo: String,
-        project_whitepaper: String,
-        project_website: String,
-        project_email: String,
-        creator_wallet: String,
-        project_collected: Uint128,
-        project_milestones: Vec<Milestone>,
-        project_teammembers: Vec<TeamMember>,
-        vesting: Vec<VestingParameter>,
-        token_addr: String,
-
-        country: String,
-        cofounder_name: String,
-        service_wefund: String,
-        service_charity: String,
-        professional_link: String,
+    SubmitProject {
+        project_info: ProjectInfo,
+    },
+    UpdateProject {
+        project_id: Uint64,
+        project_info: ProjectInfo,
     },
     RemoveProject {
         project_id: Uint64,
     },
     Back2ProjectWithout {
+        attestation: Attestation,
+        pubkey: Binary,    //registered relayer key
+        signature: Binary, //secp256k1 over sha256 of attestation json
+    },
+    AddRelayerKey {
+        pubkey: Binary,
+    },
+    SetMerkleRoot {
         project_id: Uint64,
-        backer_wallet: String,
-        denom: String,
-        amount: Uint128,
-        fundraising_stage: Uint128,
-        token_amount: Uint128,
-        otherchain: String,
-        otherchain_wallet: String,
+        root: Binary,
+    },
+    RemoveRelayerKey {
+        pubkey: Binary,
     },
     Back2Project {
         project_id: Uint64,
@@ -192,14 +490,25 @@ pub enum ExecuteMsg {
         token_amount: Uint128,
         otherchain: String,
         otherchain_wallet: String,
+        whitelist_proof: Option<WhitelistProof>, //backing on merkle whitelist
     },
+    Receive(Cw20ReceiveMsg),
 
     CompleteProject {
         project_id: Uint64,
     },
+    FinalizeFundraising {
+        project_id: Uint64,
+    },
+    OpenPublicSale {
+        project_id: Uint64,
+    },
     FailProject {
         project_id: Uint64,
     },
+    ClaimRefund {
+        project_id: Uint64,
+    },
 
     TransferAllCoins {
         wallet: String,
@@ -212,8 +521,60 @@ pub enum ExecuteMsg {
         wallet: String,
     },
 
-    WefundApprove {
+    SetAcceptedDenom {
+        denom: String,
+        is_cw20: bool,
+        rate: Decimal,
+    },
+    RemoveAcceptedDenom {
+        denom: String,
+    },
+
+    ProposeProject {
+        project_id: Uint64,
+        fee_bps: Option<Uint64>,
+    },
+    SetApprovalVote {
+        project_id: Uint64,
+        option: VoteOption,
+    },
+    TallyApproval {
+        project_id: Uint64,
+    },
+    VetoProject {
         project_id: Uint64,
+        reason: String,
+    },
+    SetApprovalConfig {
+        quorum: Decimal,
+        threshold: Decimal,
+        vote_period: Uint64,
+    },
+    //------------cw721 on backer positions, token_id is position id-------
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    RevokeAll {
+        operator: String,
     },
     SetFundraisingStage {
         project_id: Uint64,
@@ -222,8 +583,20 @@ pub enum ExecuteMsg {
 
     SetMilestoneVote {
         project_id: Uint64,
-        wallet: String,
-        voted: bool,
+        option: VoteOption,
+    },
+    SetVotingConfig {
+        project_id: Uint64,
+        quorum: Decimal,
+        threshold: Decimal,
+        vote_period: Uint64,
+        max_rejections: Uint64,
+    },
+    TallyMilestone {
+        project_id: Uint64,
+    },
+    ReopenMilestoneVote {
+        project_id: Uint64,
     },
 
     ReleaseMilestone {
@@ -232,29 +605,169 @@ pub enum ExecuteMsg {
 
     SetProjectStatus {
         project_id: Uint64,
-        status: Uint128,
+        status: ProjectStatus,
     },
 
     OpenWhitelist {
         project_id: Uint64,
         holder_alloc: Uint128,
+        tier_weights: Option<TierWeights>,
+        lottery: Option<LotterySlots>,
+        whitelist_period: Option<Period>,
+        fundraising_period: Option<Period>,
+        public_sale: Option<PublicSale>,
     },
     RegisterWhitelist {
         project_id: Uint64,
-        card_type: CardType,
+    },
+    RecheckWhitelist {
+        project_id: Uint64,
+        limit: Option<u32>,
     },
     CloseWhitelist {
         project_id: Uint64,
     },
+    CommitLotterySeed {
+        project_id: Uint64,
+        commitment: String,
+    },
+    RevealLotterySeed {
+        project_id: Uint64,
+        secret: Binary,
+    },
+    GrantRole {
+        wallet: String,
+        role: Role,
+    },
+    RevokeRole {
+        wallet: String,
+        role: Role,
+    },
+}
+
+//---------hook message sent along with cw20 funds-----------------
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveMsg {
+    Back2Project {
+        project_id: Uint64,
+        backer_wallet: String,
+        fundraising_stage: Uint128,
+        token_amount: Uint128,
+        otherchain: String,
+        otherchain_wallet: String,
+        whitelist_proof: Option<WhitelistProof>, //backing on merkle whitelist
+    },
+    SubmitProject {
+        project_info: ProjectInfo,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     GetConfig {},
-    GetAllProject {},
-    GetProject { project_id: Uint64 },
-    GetBacker { project_id: Uint64 },
-    GetBalance { wallet: String },
+    GetAllProject {
+        start_after: Option<Uint64>,
+        limit: Option<u32>,
+        filter: Option<ProjectFilter>,
+    },
+    GetProjectSummaries {
+        start_after: Option<Uint64>,
+        limit: Option<u32>,
+        filter: Option<ProjectFilter>,
+    },
+    GetProject {
+        project_id: Uint64,
+    },
+    GetBacker {
+        project_id: Uint64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    GetWhitelist {
+        project_id: Uint64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    GetMilestones {
+        project_id: Uint64,
+    },
+    GetVote {
+        project_id: Uint64,
+        wallet: String,
+    },
+    GetBalance {
+        wallet: String,
+    },
     GetCommunitymembers {},
+    GetAcceptedDenoms {},
+    GetRefundable {
+        project_id: Uint64,
+        wallet: String,
+    },
+    GetAllocation {
+        project_id: Uint64,
+        wallet: String,
+    },
+    GetMilestoneTally {
+        project_id: Uint64,
+    },
+    GetRoles {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    GetBond {
+        project_id: Uint64,
+    },
+    GetApprovalTally {
+        project_id: Uint64,
+    },
+    GetPosition {
+        token_id: Uint64,
+    },
+    GetPositions {
+        owner: String,
+        project_id: Option<Uint64>,
+        start_after: Option<Uint64>,
+        limit: Option<u32>,
+    },
+    GetRelayerKeys {},
+    GetAttestation {
+        otherchain: String,
+        source_tx_hash: String,
+    },
+    PreviewAllocation {
+        project_id: Uint64,
+    },
+    GetLottery {
+        project_id: Uint64,
+    },
+    GetMerkleRoot {
+        project_id: Uint64,
+    },
+
+    //------------cw721 on backer positions--------------------------------
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    NftInfo {
+        token_id: String,
+    },
+    AllNftInfo {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    NumTokens {},
+    ContractInfo {},
 }